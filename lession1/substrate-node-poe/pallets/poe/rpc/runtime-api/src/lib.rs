@@ -0,0 +1,20 @@
+//! Runtime API definition for the PoE pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	pub trait PoeApi<AccountId, BlockNumber> where
+		AccountId: Codec,
+		BlockNumber: Codec,
+	{
+		/// 存证的 (所有者, 区块)
+		fn proof_owner(proof: Vec<u8>) -> Option<(AccountId, BlockNumber)>;
+		/// 分页查询账号拥有的存证，跳过 start 条后最多返回 count 条
+		fn proofs_of(account: AccountId, start: u32, count: u32) -> Vec<Vec<u8>>;
+		/// 存证在链下索引中的历史条数
+		fn history_count(proof: Vec<u8>) -> u32;
+	}
+}
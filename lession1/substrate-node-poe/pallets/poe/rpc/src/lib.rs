@@ -0,0 +1,117 @@
+//! RPC interface for the PoE pallet.
+
+use std::sync::Arc;
+use codec::{Codec, Decode};
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{Bytes, offchain::OffchainStorage};
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use pallet_poe_runtime_api::PoeApi as PoeRuntimeApi;
+pub use pallet_poe::{HistoryRecord, HistoryAction};
+
+#[rpc]
+pub trait PoeApi<BlockHash, AccountId, BlockNumber> {
+	/// 查询存证的 (所有者, 区块)
+	#[rpc(name = "poe_getProof")]
+	fn get_proof(&self, proof: Bytes, at: Option<BlockHash>) -> Result<Option<(AccountId, BlockNumber)>>;
+
+	/// 分页查询账号拥有的存证，跳过 start 条后最多返回 count 条，count 上限为 MAX_PROOFS_PER_PAGE
+	#[rpc(name = "poe_getProofsOf")]
+	fn get_proofs_of(&self, account: AccountId, start: u32, count: u32, at: Option<BlockHash>) -> Result<Vec<Bytes>>;
+
+	/// 查询存证的创建、转移、撤销历史，按发生顺序排列
+	#[rpc(name = "poe_getHistory")]
+	fn get_history(&self, proof: Bytes, at: Option<BlockHash>) -> Result<Vec<HistoryRecord<AccountId, BlockNumber>>>;
+}
+
+/// A struct that implements the `PoeApi`.
+pub struct Poe<C, B, S> {
+	client: Arc<C>,
+	/// 链下数据库，节点未启用链下存储时为 None
+	offchain_storage: Option<S>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B, S> Poe<C, B, S> {
+	/// Create new `Poe` with the given reference to the client and the offchain storage.
+	pub fn new(client: Arc<C>, offchain_storage: Option<S>) -> Self {
+		Self { client, offchain_storage, _marker: Default::default() }
+	}
+}
+
+/// poe_getProofsOf 每页最多返回的存证数量
+pub const MAX_PROOFS_PER_PAGE: u32 = 1_000;
+
+/// Error code for runtime API call failures.
+const RUNTIME_ERROR: i64 = 1;
+
+fn runtime_error(e: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(RUNTIME_ERROR),
+		message: "Unable to query PoE runtime API.".into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+/// Error code for missing offchain storage.
+const OFFCHAIN_ERROR: i64 = 2;
+
+impl<C, Block, S, AccountId, BlockNumber> PoeApi<<Block as BlockT>::Hash, AccountId, BlockNumber> for Poe<C, Block, S>
+where
+	S: OffchainStorage + 'static,
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: PoeRuntimeApi<Block, AccountId, BlockNumber>,
+	AccountId: Codec,
+	BlockNumber: Codec,
+{
+	fn get_proof(
+		&self,
+		proof: Bytes,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<(AccountId, BlockNumber)>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.proof_owner(&at, proof.to_vec()).map_err(runtime_error)
+	}
+
+	fn get_proofs_of(
+		&self,
+		account: AccountId,
+		start: u32,
+		count: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<Bytes>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.proofs_of(&at, account, start, count.min(MAX_PROOFS_PER_PAGE))
+			.map(|proofs| proofs.into_iter().map(Bytes::from).collect())
+			.map_err(runtime_error)
+	}
+
+	fn get_history(
+		&self,
+		proof: Bytes,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<HistoryRecord<AccountId, BlockNumber>>> {
+		let storage = self.offchain_storage.as_ref().ok_or_else(|| RpcError {
+			code: ErrorCode::ServerError(OFFCHAIN_ERROR),
+			message: "Offchain storage is not available.".into(),
+			data: None,
+		})?;
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let count = api.history_count(&at, proof.to_vec()).map_err(runtime_error)?;
+		// 节点中途才启用链下索引时，更早的记录缺失，跳过即可
+		Ok((0..count)
+			.filter_map(|index| storage.get(sp_offchain::STORAGE_PREFIX, &pallet_poe::history_key(&proof, index)))
+			.filter_map(|raw| Decode::decode(&mut &raw[..]).ok())
+			.collect())
+	}
+}
@@ -0,0 +1,119 @@
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+use frame_benchmarking::{benchmarks, account};
+use frame_system::RawOrigin;
+use frame_support::traits::OnInitialize;
+use sp_runtime::traits::Bounded;
+use sp_std::prelude::*;
+
+use crate::Module as PoeModule;
+
+/// 文档基准测试的最大长度，权重按线性外推
+const MAX_BENCHMARK_DOCUMENT_LEN: u32 = 1024 * 1024;
+
+/// 创建一个余额充足的账号
+fn funded_account<T: Trait>(name: &'static str, index: u32) -> T::AccountId {
+	let who: T::AccountId = account(name, index, 0);
+	T::Currency::make_free_balance_be(&who, BalanceOf::<T>::max_value() / 2u32.into());
+	who
+}
+
+benchmarks!{
+	_ {
+		let l in 1 .. T::MaxProofLength::get() as u32 => ();
+	}
+
+	claim_created {
+		let l in ...;
+		let caller = funded_account::<T>("caller", 0);
+		let proof = vec![0u8; l as usize];
+	}: _ (RawOrigin::Signed(caller.clone()), proof.clone(), None, None)
+	verify { // 验证
+		assert_eq!(Proofs::<T>::get(&proof).map(|p| p.owner), Some(caller));
+	}
+
+	claim_revoked {
+		let l in ...;
+		let caller = funded_account::<T>("caller", 0);
+		let proof = vec![0u8; l as usize];
+		PoeModule::<T>::claim_created(RawOrigin::Signed(caller.clone()).into(), proof.clone(), None, None)?;
+	}: _ (RawOrigin::Signed(caller), proof.clone())
+	verify {
+		assert!(!Proofs::<T>::contains_key(&proof));
+	}
+
+	transfer_claim {
+		let l in ...;
+		let caller = funded_account::<T>("caller", 0);
+		let dest = funded_account::<T>("dest", 0);
+		let proof = vec![0u8; l as usize];
+		PoeModule::<T>::claim_created(RawOrigin::Signed(caller.clone()).into(), proof.clone(), None, None)?;
+	}: _ (RawOrigin::Signed(caller), proof.clone(), dest.clone())
+	verify {
+		assert_eq!(Proofs::<T>::get(&proof).map(|p| p.owner), Some(dest));
+	}
+
+	// 取三种算法中最慢的 Keccak256
+	verify_document {
+		let l in 0 .. MAX_BENCHMARK_DOCUMENT_LEN;
+		let caller = funded_account::<T>("caller", 0);
+		let document = vec![1u8; l as usize];
+		let algorithm = HashAlgorithm::Keccak256;
+		PoeModule::<T>::claim_hash(RawOrigin::Signed(caller.clone()).into(), algorithm, algorithm.hash(&document), None, None)?;
+	}: _ (RawOrigin::Signed(caller), algorithm, document)
+
+	// 锚定的 leaf_count 恰好允许 p 步的包含证明
+	verify_inclusion {
+		let l in 0 .. MAX_BENCHMARK_DOCUMENT_LEN;
+		let p in 0 .. MAX_MERKLE_DEPTH as u32;
+		let caller = funded_account::<T>("caller", 0);
+		let document = vec![1u8; l as usize];
+		let path: Vec<MerkleStep> = (0 .. p).map(|i| MerkleStep::Left([i as u8; 32])).collect();
+		let root = merkle_root_from_path(&document, &path);
+		let leaf_count = if p >= 32 { u32::max_value() } else { 1u32 << p };
+		PoeModule::<T>::claim_merkle_root(RawOrigin::Signed(caller.clone()).into(), root, leaf_count)?;
+	}: _ (RawOrigin::Signed(caller), document, path, root)
+
+	// 每个到期存证都带元数据、待接受的转移，触及 remove_claim 的全部存储
+	on_initialize {
+		let e in 0 .. T::MaxExpiriesPerBlock::get();
+		let caller = funded_account::<T>("caller", 0);
+		let dest = funded_account::<T>("dest", 0);
+		let expires_at = frame_system::Module::<T>::block_number() + 1u32.into();
+		let metadata = ClaimMetadata { description: vec![0u8; T::MaxMetadataLength::get()], ..Default::default() };
+		let mut proofs = Vec::new();
+		for i in 0 .. e {
+			let mut proof = i.encode();
+			proof.resize(T::MaxProofLength::get(), 0);
+			PoeModule::<T>::claim_created(RawOrigin::Signed(caller.clone()).into(), proof.clone(), Some(metadata.clone()), Some(expires_at))?;
+			PoeModule::<T>::offer_claim(RawOrigin::Signed(caller.clone()).into(), proof.clone(), dest.clone())?;
+			proofs.push(proof);
+		}
+	}: { PoeModule::<T>::on_initialize(expires_at); }
+	verify {
+		for proof in proofs {
+			assert!(!Proofs::<T>::contains_key(&proof));
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn test_benchmarks() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_claim_created::<Test>());
+			assert_ok!(test_benchmark_claim_revoked::<Test>());
+			assert_ok!(test_benchmark_transfer_claim::<Test>());
+			assert_ok!(test_benchmark_on_initialize::<Test>());
+			assert_ok!(test_benchmark_verify_document::<Test>());
+			assert_ok!(test_benchmark_verify_inclusion::<Test>());
+		});
+	}
+}
@@ -1,9 +1,24 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use frame_support::{decl_module, decl_storage, decl_event, decl_error, ensure};
+use codec::{Encode, Decode};
+#[cfg(feature = "std")]
+use serde::{Serialize, Deserialize};
+use frame_support::{decl_module, decl_storage, decl_event, decl_error, ensure, weights::Weight,
+	storage::{with_transaction, TransactionOutcome},
+};
 use frame_system::ensure_signed;
 use sp_std::vec::Vec;
-use frame_support::traits::Get;
+use sp_io::hashing::{blake2_256, sha2_256, keccak_256};
+use sp_runtime::{DispatchError, RuntimeDebug, traits::{Saturating, Zero}};
+use frame_support::traits::{Get, Currency, ReservableCurrency, BalanceStatus, EnsureOrigin};
+
+mod benchmarking;
+pub mod weights;
+pub mod migration;
+#[cfg(feature = "std")]
+pub mod merkle;
+
+pub use weights::WeightInfo;
 
 #[cfg(test)]
 mod mock;
@@ -11,22 +26,304 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+/// 文档摘要使用的哈希算法
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum HashAlgorithm {
+	Blake2_256,
+	Sha2_256,
+	Keccak256,
+}
+
+/// 摘要存证 key 的长度：1 字节算法标记 + 32 字节摘要
+pub const HASH_PROOF_KEY_LEN: usize = 33;
+
+impl HashAlgorithm {
+	/// 计算文档摘要
+	pub fn hash(&self, data: &[u8]) -> [u8; 32] {
+		match self {
+			HashAlgorithm::Blake2_256 => blake2_256(data),
+			HashAlgorithm::Sha2_256 => sha2_256(data),
+			HashAlgorithm::Keccak256 => keccak_256(data),
+		}
+	}
+}
+
+/// 存证历史中的操作
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum HistoryAction {
+	Created,
+	Transferred,
+	Revoked,
+	Expired,
+}
+
+/// 链下索引中的一条存证历史
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct HistoryRecord<AccountId, BlockNumber> {
+	pub action: HistoryAction,
+	/// 操作后的所有者，撤销或过期时为原所有者
+	pub owner: AccountId,
+	pub block: BlockNumber,
+}
+
+/// 链下数据库中存证历史的 key 前缀
+pub const HISTORY_PREFIX: &[u8] = b"poe::history";
+
+/// 存证第 index 条历史在链下数据库中的 key
+pub fn history_key(proof: &[u8], index: u32) -> Vec<u8> {
+	(HISTORY_PREFIX, proof, index).encode()
+}
+
+/// 包含证明中的一步：兄弟节点及其所在的一侧
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum MerkleStep {
+	Left([u8; 32]),
+	Right([u8; 32]),
+}
+
+/// 包含证明的最大长度，leaf_count 为 u32 时树高不超过 32
+pub const MAX_MERKLE_DEPTH: usize = 32;
+
+/// leaf_count 个叶子的树高，即 ceil(log2(leaf_count))，包含证明不会更长
+pub fn merkle_depth(leaf_count: u32) -> usize {
+	(32 - leaf_count.saturating_sub(1).leading_zeros()) as usize
+}
+
+/// Merkle 叶子哈希，加前缀 0 与内部节点区分
+pub fn merkle_leaf(document: &[u8]) -> [u8; 32] {
+	let mut data = Vec::with_capacity(document.len() + 1);
+	data.push(0u8);
+	data.extend_from_slice(document);
+	blake2_256(&data)
+}
+
+/// Merkle 内部节点哈希，加前缀 1
+pub fn merkle_parent(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+	let mut data = [0u8; 65];
+	data[0] = 1;
+	data[1..33].copy_from_slice(left);
+	data[33..].copy_from_slice(right);
+	blake2_256(&data)
+}
+
+/// 按包含证明由文档计算树根
+pub fn merkle_root_from_path(document: &[u8], path: &[MerkleStep]) -> [u8; 32] {
+	path.iter().fold(merkle_leaf(document), |node, step| match step {
+		MerkleStep::Left(sibling) => merkle_parent(sibling, &node),
+		MerkleStep::Right(sibling) => merkle_parent(&node, sibling),
+	})
+}
+
+/// 链上锚定的 Merkle 根
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct MerkleRoot<AccountId, BlockNumber, Balance> {
+	pub owner: AccountId,
+	/// 批次中的文档数量
+	pub leaf_count: u32,
+	pub created_at: BlockNumber,
+	pub deposit: Balance,
+}
+
+/// 存证附带的元数据
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
+pub struct ClaimMetadata {
+	/// 描述
+	pub description: Vec<u8>,
+	/// MIME 类型
+	pub content_type: Vec<u8>,
+	/// 链下存储地址
+	pub uri: Vec<u8>,
+}
+
+/// 存证信息
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct ProofInfo<AccountId, BlockNumber, Balance> {
+	/// 所有者
+	pub owner: AccountId,
+	/// 最初创建的区块，转移后保持不变
+	pub created_at: BlockNumber,
+	/// 最近一次转移的区块
+	pub updated_at: BlockNumber,
+	/// 实际质押的押金，撤销时按此退还
+	pub deposit: Balance,
+}
+
+/// 存储结构版本
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum Releases {
+	/// Proofs 值为 (AccountId, BlockNumber)
+	V1,
+	/// Proofs 值为 ProofInfo
+	V2,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V1
+	}
+}
+
+/// 对存证的争议，处理前存证被冻结
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Dispute<AccountId, Balance> {
+	/// 发起争议的账号
+	pub disputer: AccountId,
+	/// 争议原因代码，含义由治理约定
+	pub reason: u32,
+	/// 发起方质押的押金，争议被驳回时赔付给所有者
+	pub deposit: Balance,
+}
+
+/// 多方共有存证的所有者及门限
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct CoOwnership<AccountId> {
+	/// 全部所有者，包含创建者
+	pub owners: Vec<AccountId>,
+	/// 执行操作所需的最少批准数
+	pub threshold: u32,
+}
+
+/// 多方共有存证需要批准的操作
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum ClaimAction<AccountId> {
+	Revoke,
+	Transfer(AccountId),
+}
+
+/// 收集中的批准
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct PendingApproval<AccountId, BlockNumber> {
+	pub action: ClaimAction<AccountId>,
+	pub approvals: Vec<AccountId>,
+	/// 到此区块仍未达到门限则作废
+	pub expires_at: BlockNumber,
+}
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
+pub type ProofInfoOf<T> = ProofInfo<<T as frame_system::Trait>::AccountId, <T as frame_system::Trait>::BlockNumber, BalanceOf<T>>;
+pub type MerkleRootOf<T> = MerkleRoot<<T as frame_system::Trait>::AccountId, <T as frame_system::Trait>::BlockNumber, BalanceOf<T>>;
+
 pub trait Trait: frame_system::Trait {
     type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
     type MaxProofLength: Get<usize>;
+    // 元数据每个字段的最大长度
+    type MaxMetadataLength: Get<usize>;
+    type Currency: ReservableCurrency<Self::AccountId>; // 质押
+    // 押金 = 基础押金 + 每字节押金 * 存证长度
+    type ProofDepositBase: Get<BalanceOf<Self>>;
+    type ProofDepositPerByte: Get<BalanceOf<Self>>;
+    // 每个区块最多过期的存证数量，限制 on_initialize 的工作量
+    type MaxExpiriesPerBlock: Get<u32>;
+    // 批量存证/撤销的最大数量
+    type MaxBatchSize: Get<u32>;
+    // 多方共有存证的最大所有者数量
+    type MaxCoOwners: Get<u32>;
+    // 批准收集的有效区块数
+    type ApprovalTimeout: Get<Self::BlockNumber>;
+    // 可强制撤销存证、处理争议的来源，通常为 Root 或议会
+    type ForceOrigin: EnsureOrigin<Self::Origin>;
+    // 发起争议需质押的押金
+    type DisputeDeposit: Get<BalanceOf<Self>>;
+    // 删除存证时从押金中扣除并销毁的部分，支付永久保留的 HistoryCount，应不大于 ProofDepositBase
+    type HistoryDeposit: Get<BalanceOf<Self>>;
+    type WeightInfo: WeightInfo;
 }
 
 decl_storage! {
 	trait Store for Module<T: Trait> as PoeModule {
-		Proofs:map hasher(blake2_128_concat) Vec<u8> => (T::AccountId, T::BlockNumber);
+		// 原始字节存证与摘要存证共用，摘要存证的 key 见 `hash_proof_key`，原始字节存证不能与其同形
+		pub Proofs get(fn proofs): map hasher(blake2_128_concat) Vec<u8> => Option<ProofInfoOf<T>>;
+		// 存储结构版本，新链直接为 V2
+		StorageVersion build(|_| Releases::V2): Releases;
+		// 待接受的转移 proof => 接收方
+		pub PendingOffers get(fn pending_offer): map hasher(blake2_128_concat) Vec<u8> => Option<T::AccountId>;
+		// 多方共有存证，Proofs 中记录的所有者为创建者
+		pub CoOwners get(fn co_owners): map hasher(blake2_128_concat) Vec<u8> => Option<CoOwnership<T::AccountId>>;
+		// 多方共有存证待批准的操作
+		pub PendingApprovals get(fn pending_approval): map hasher(blake2_128_concat) Vec<u8>
+			=> Option<PendingApproval<T::AccountId, T::BlockNumber>>;
+		// 存证元数据
+		pub Metadata get(fn metadata): map hasher(blake2_128_concat) Vec<u8> => Option<ClaimMetadata>;
+		// 账号拥有的存证，double_map方便按账号遍历
+		pub OwnedProofs get(fn owned_proofs): double_map hasher(blake2_128_concat) T::AccountId, hasher(blake2_128_concat) Vec<u8> => ();
+		// 账号拥有的存证数量
+		pub OwnedProofsCount get(fn owned_proofs_count): map hasher(blake2_128_concat) T::AccountId => u32;
+		// 存证过期区块
+		pub Expiries get(fn expiry_of): map hasher(blake2_128_concat) Vec<u8> => Option<T::BlockNumber>;
+		// 每个区块到期的存证
+		pub ExpiryQueue get(fn expiring_at): map hasher(twox_64_concat) T::BlockNumber => Vec<Vec<u8>>;
+		// 存证在链下索引中的历史条数，撤销后保留以便重新创建时继续追加，其存储费用由 HistoryDeposit 支付
+		pub HistoryCount get(fn history_count): map hasher(blake2_128_concat) Vec<u8> => u32;
+		// 争议中的存证
+		pub Disputes get(fn dispute_of): map hasher(blake2_128_concat) Vec<u8> => Option<Dispute<T::AccountId, BalanceOf<T>>>;
+		// 批量锚定的 Merkle 根
+		pub MerkleRoots get(fn merkle_root): map hasher(blake2_128_concat) [u8; 32] => Option<MerkleRootOf<T>>;
+	}
+	add_extra_genesis {
+		// 创世存证 (proof, owner)，不质押押金
+		config(proofs): Vec<(Vec<u8>, T::AccountId)>;
+		build(|config: &GenesisConfig<T>| {
+			for (proof, owner) in config.proofs.iter() {
+				assert!(proof.len() <= T::MaxProofLength::get(), "Genesis proof exceeds MaxProofLength");
+				assert!(!<Module<T>>::is_hash_proof_key(proof), "Genesis proof collides with a hash proof key");
+				assert!(!Proofs::<T>::contains_key(proof), "Duplicate genesis proof");
+				Proofs::<T>::insert(proof, ProofInfo {
+					owner: owner.clone(),
+					created_at: T::BlockNumber::zero(),
+					updated_at: T::BlockNumber::zero(),
+					deposit: Zero::zero(),
+				});
+				<Module<T>>::insert_owned_proof(owner, proof);
+			}
+		});
 	}
 }
 
 decl_event!(
-	pub enum Event<T> where AccountId = <T as frame_system::Trait>::AccountId {
-		ClaimCreated(AccountId, Vec<u8>),
+	pub enum Event<T> where
+		AccountId = <T as frame_system::Trait>::AccountId,
+		BlockNumber = <T as frame_system::Trait>::BlockNumber,
+	{
+		/// [owner, proof, metadata]
+		ClaimCreated(AccountId, Vec<u8>, Option<ClaimMetadata>),
 		ClaimRevoked(AccountId, Vec<u8>),
 		TransferClaim(AccountId, Vec<u8>, AccountId),
+		/// 文档校验通过 [owner, proof]
+		DocumentVerified(AccountId, Vec<u8>),
+		/// 元数据更新 [owner, proof, metadata]
+		MetadataUpdated(AccountId, Vec<u8>, Option<ClaimMetadata>),
+		/// 存证过期被移除 [owner, proof]
+		ClaimExpired(AccountId, Vec<u8>),
+		/// 存证续期 [owner, proof, expires_at]
+		ClaimRenewed(AccountId, Vec<u8>, BlockNumber),
+		/// 发起转移 [owner, proof, dest]
+		ClaimOffered(AccountId, Vec<u8>, AccountId),
+		/// 取消转移 [owner, proof]
+		OfferCancelled(AccountId, Vec<u8>),
+		/// 批量操作完成 [who, succeeded, failed_indices]
+		BatchCompleted(AccountId, u32, Vec<u32>),
+		/// 创建多方共有存证 [creator, proof, owners, threshold]
+		CoOwnedClaimCreated(AccountId, Vec<u8>, Vec<AccountId>, u32),
+		/// 批准操作 [who, proof, action, approvals]
+		ActionApproved(AccountId, Vec<u8>, ClaimAction<AccountId>, u32),
+		/// 达到门限，操作已执行 [proof, action]
+		ActionExecuted(Vec<u8>, ClaimAction<AccountId>),
+		/// 批准超时作废 [proof, action]
+		ApprovalExpired(Vec<u8>, ClaimAction<AccountId>),
+		/// 发起争议，存证被冻结 [disputer, proof, reason]
+		ClaimDisputed(AccountId, Vec<u8>, u32),
+		/// 争议被驳回，存证解冻 [disputer, proof, reason]
+		DisputeRejected(AccountId, Vec<u8>, u32),
+		/// 存证被强制撤销 [owner, proof, reason]
+		ClaimForceRevoked(AccountId, Vec<u8>, u32),
+		/// 锚定 Merkle 根 [owner, root, leaf_count]
+		MerkleRootClaimed(AccountId, [u8; 32], u32),
+		/// 撤销 Merkle 根 [owner, root]
+		MerkleRootRevoked(AccountId, [u8; 32]),
+		/// 文档包含于已锚定的批次 [owner, root]
+		InclusionVerified(AccountId, [u8; 32]),
 	}
 );
 
@@ -36,6 +333,31 @@ decl_error! {
 		NoSuchProof,
 		NotProofOwner,
 		ProofTooLong,
+		ReservedProofKey,
+		MetadataTooLong,
+		InsufficientDeposit,
+		InvalidExpiry,
+		TooManyExpiries,
+		ClaimNotExpiring,
+		NoPendingOffer,
+		NotOfferRecipient,
+		BatchTooLarge,
+		TooManyCoOwners,
+		InvalidThreshold,
+		NotCoOwnedClaim,
+		RequiresApproval,
+		AlreadyApproved,
+		ConflictingAction,
+		NoPendingApproval,
+		ApprovalNotExpired,
+		MerkleRootAlreadyClaimed,
+		NoSuchMerkleRoot,
+		InvalidLeafCount,
+		InvalidMerkleProof,
+		ProofDisputed,
+		AlreadyDisputed,
+		NoSuchDispute,
+		RecipientNotFound,
 	}
 }
 
@@ -46,42 +368,610 @@ decl_module! {
 		fn deposit_event() = default;
         // const MaxLength: u32 = T::MaxProofLength::get() as u32;
 
-		#[weight = 10_000]
-		pub fn claim_created(origin, proof: Vec<u8>){
+		fn on_runtime_upgrade() -> Weight {
+			migration::migrate_to_v2::<T>()
+		}
+
+		/// 移除本区块到期的存证，数量受 MaxExpiriesPerBlock 限制
+		fn on_initialize(n: T::BlockNumber) -> Weight {
+			let expiring = ExpiryQueue::<T>::take(n);
+			let count = expiring.len() as u32;
+			for proof in expiring {
+				Self::expire_claim(&proof);
+			}
+			T::WeightInfo::on_initialize(count)
+		}
+
+		#[weight = T::WeightInfo::claim_created(proof.len() as u32)]
+		pub fn claim_created(origin, proof: Vec<u8>, metadata: Option<ClaimMetadata>, expires_at: Option<T::BlockNumber>){
 
 			let sender = ensure_signed(origin)?;
-			ensure!(proof.len() <= T::MaxProofLength::get(), Error::<T>::ProofTooLong); // 限制长度
+			Self::ensure_raw_proof_valid(&proof)?; // 限制长度
 
-			ensure!(!Proofs::<T>::contains_key(&proof), Error::<T>::ProofAlreadyClaimed);
-			let current_block = <frame_system::Module<T>>::block_number();
-			Proofs::<T>::insert(&proof,(sender.clone(),current_block));
-			Self::deposit_event(RawEvent::ClaimCreated(sender, proof));
+			Self::do_create_claim(&sender, &proof, &metadata, expires_at)?;
+			Self::deposit_event(RawEvent::ClaimCreated(sender, proof, metadata));
 		}
 
-		#[weight = 10_000]
+		/// 以文档摘要创建存证，摘要长度固定，不受 MaxProofLength 限制
+		#[weight = T::WeightInfo::claim_created(HASH_PROOF_KEY_LEN as u32)]
+		pub fn claim_hash(origin, algorithm: HashAlgorithm, digest: [u8; 32], metadata: Option<ClaimMetadata>, expires_at: Option<T::BlockNumber>){
+			let sender = ensure_signed(origin)?;
+
+			let proof = Self::hash_proof_key(algorithm, &digest);
+			Self::do_create_claim(&sender, &proof, &metadata, expires_at)?;
+			Self::deposit_event(RawEvent::ClaimCreated(sender, proof, metadata));
+		}
+
+		#[weight = T::WeightInfo::claim_revoked(proof.len() as u32)]
 		pub fn claim_revoked(origin, proof: Vec<u8>){
 			let sender = ensure_signed(origin)?;
 
-			ensure!(Proofs::<T>::contains_key(&proof), Error::<T>::NoSuchProof);
-			let (owner, _) = Proofs::<T>::get(&proof);
+			let owner = Proofs::<T>::get(&proof).ok_or(Error::<T>::NoSuchProof)?.owner;
 			ensure!(sender==owner,Error::<T>::NotProofOwner);
-			Proofs::<T>::remove(&proof);
+			ensure!(!CoOwners::<T>::contains_key(&proof), Error::<T>::RequiresApproval);
+			Self::ensure_not_disputed(&proof)?;
+			Self::remove_claim(&sender, &proof, HistoryAction::Revoked);
 
 			Self::deposit_event(RawEvent::ClaimRevoked(sender, proof));
 
 		}
 
-		#[weight = 10_000]
+		/// 批量创建存证，atomic 为 true 时任一失败则全部回滚，否则跳过失败项
+		#[weight = T::WeightInfo::claim_created(T::MaxProofLength::get() as u32)
+			.saturating_mul(proofs.len() as Weight)]
+		pub fn claim_batch(origin, proofs: Vec<Vec<u8>>, atomic: bool){
+			let sender = ensure_signed(origin)?;
+			ensure!(proofs.len() as u32 <= T::MaxBatchSize::get(), Error::<T>::BatchTooLarge);
+
+			Self::do_batch(&sender, proofs, atomic, |who, proof| {
+				Self::ensure_raw_proof_valid(&proof)?;
+				Self::do_create_claim(who, &proof, &None, None)?;
+				Self::deposit_event(RawEvent::ClaimCreated(who.clone(), proof, None));
+				Ok(())
+			})?;
+		}
+
+		/// 批量撤销存证，语义同 claim_batch
+		#[weight = T::WeightInfo::claim_revoked(T::MaxProofLength::get() as u32)
+			.saturating_mul(proofs.len() as Weight)]
+		pub fn revoke_batch(origin, proofs: Vec<Vec<u8>>, atomic: bool){
+			let sender = ensure_signed(origin)?;
+			ensure!(proofs.len() as u32 <= T::MaxBatchSize::get(), Error::<T>::BatchTooLarge);
+
+			Self::do_batch(&sender, proofs, atomic, |who, proof| {
+				let owner = Proofs::<T>::get(&proof).ok_or(Error::<T>::NoSuchProof)?.owner;
+				ensure!(who==&owner,Error::<T>::NotProofOwner);
+				ensure!(!CoOwners::<T>::contains_key(&proof), Error::<T>::RequiresApproval);
+				Self::ensure_not_disputed(&proof)?;
+				Self::remove_claim(who, &proof, HistoryAction::Revoked);
+				Self::deposit_event(RawEvent::ClaimRevoked(who.clone(), proof));
+				Ok(())
+			})?;
+		}
+
+		#[weight = T::WeightInfo::transfer_claim(proof.len() as u32)]
 		pub fn transfer_claim(origin, proof: Vec<u8>, dest: T::AccountId){
 			let sender = ensure_signed(origin)?;
 
-			ensure!(Proofs::<T>::contains_key(&proof), Error::<T>::NoSuchProof);
-			let (owner, _block_number) = Proofs::<T>::get(&proof);
+			let owner = Proofs::<T>::get(&proof).ok_or(Error::<T>::NoSuchProof)?.owner;
 			ensure!(sender==owner,Error::<T>::NotProofOwner);
-			Proofs::<T>::insert(&proof,(dest.clone(),frame_system::Module::<T>::block_number()));
+			ensure!(!CoOwners::<T>::contains_key(&proof), Error::<T>::RequiresApproval);
+			Self::ensure_not_disputed(&proof)?;
+			Self::do_transfer(&sender, &proof, &dest)?;
 
 			Self::deposit_event(RawEvent::TransferClaim(sender, proof, dest));
 
 		}
+
+		/// 发起转移，需接收方 accept_claim 后生效
+		#[weight = T::DbWeight::get().reads_writes(3, 1)]
+		pub fn offer_claim(origin, proof: Vec<u8>, dest: T::AccountId){
+			let sender = ensure_signed(origin)?;
+
+			let owner = Proofs::<T>::get(&proof).ok_or(Error::<T>::NoSuchProof)?.owner;
+			ensure!(sender==owner,Error::<T>::NotProofOwner);
+			ensure!(!CoOwners::<T>::contains_key(&proof), Error::<T>::RequiresApproval);
+			Self::ensure_not_disputed(&proof)?;
+			PendingOffers::<T>::insert(&proof, &dest);
+
+			Self::deposit_event(RawEvent::ClaimOffered(sender, proof, dest));
+		}
+
+		/// 接收方接受转移，与 transfer_claim 相同，另读取 PendingOffers
+		#[weight = T::WeightInfo::transfer_claim(proof.len() as u32).saturating_add(T::DbWeight::get().reads(1))]
+		pub fn accept_claim(origin, proof: Vec<u8>){
+			let sender = ensure_signed(origin)?;
+
+			let dest = Self::pending_offer(&proof).ok_or(Error::<T>::NoPendingOffer)?;
+			ensure!(sender==dest,Error::<T>::NotOfferRecipient);
+			let owner = Proofs::<T>::get(&proof).ok_or(Error::<T>::NoSuchProof)?.owner;
+			Self::do_transfer(&owner, &proof, &sender)?;
+
+			Self::deposit_event(RawEvent::TransferClaim(owner, proof, sender));
+		}
+
+		/// 所有者撤回转移
+		#[weight = T::DbWeight::get().reads_writes(2, 1)]
+		pub fn cancel_offer(origin, proof: Vec<u8>){
+			let sender = ensure_signed(origin)?;
+
+			let owner = Proofs::<T>::get(&proof).ok_or(Error::<T>::NoSuchProof)?.owner;
+			ensure!(sender==owner,Error::<T>::NotProofOwner);
+			ensure!(PendingOffers::<T>::contains_key(&proof), Error::<T>::NoPendingOffer);
+			PendingOffers::<T>::remove(&proof);
+
+			Self::deposit_event(RawEvent::OfferCancelled(sender, proof));
+		}
+
+		/// 更新元数据，None 表示清除。押金按新的元数据长度补足或退还
+		#[weight = T::DbWeight::get().reads_writes(3, 3)]
+		pub fn set_metadata(origin, proof: Vec<u8>, metadata: Option<ClaimMetadata>){
+			let sender = ensure_signed(origin)?;
+
+			let mut info = Proofs::<T>::get(&proof).ok_or(Error::<T>::NoSuchProof)?;
+			ensure!(sender==info.owner,Error::<T>::NotProofOwner);
+			Self::ensure_not_disputed(&proof)?;
+			if let Some(ref m) = metadata {
+				Self::ensure_metadata_valid(m)?;
+			}
+			let deposit = Self::deposit_for(&proof, &metadata);
+			if deposit > info.deposit {
+				T::Currency::reserve(&sender, deposit - info.deposit).map_err(|_| Error::<T>::InsufficientDeposit)?;
+			} else {
+				T::Currency::unreserve(&sender, info.deposit - deposit);
+			}
+			info.deposit = deposit;
+			Proofs::<T>::insert(&proof, info);
+			Metadata::mutate_exists(&proof, |m| *m = metadata.clone());
+
+			Self::deposit_event(RawEvent::MetadataUpdated(sender, proof, metadata));
+		}
+
+		/// 延长存证的过期区块，只能在过期前由所有者调用。争议中的存证也可延期，以免争议驳回时已过期
+		// 新旧两个过期队列各最多 MaxExpiriesPerBlock 个存证，整体解码、过滤后重新编码写回
+		#[weight = T::DbWeight::get().reads_writes(4, 3).saturating_add(
+			(2_000 as Weight)
+				.saturating_mul(T::MaxExpiriesPerBlock::get() as Weight)
+				.saturating_mul(T::MaxProofLength::get().max(HASH_PROOF_KEY_LEN) as Weight)
+		)]
+		pub fn renew_claim(origin, proof: Vec<u8>, expires_at: T::BlockNumber){
+			let sender = ensure_signed(origin)?;
+
+			let owner = Proofs::<T>::get(&proof).ok_or(Error::<T>::NoSuchProof)?.owner;
+			ensure!(sender==owner,Error::<T>::NotProofOwner);
+			let old_expiry = Self::expiry_of(&proof).ok_or(Error::<T>::ClaimNotExpiring)?;
+			ensure!(expires_at > old_expiry, Error::<T>::InvalidExpiry);
+			Self::ensure_expiry_valid(expires_at)?;
+
+			Self::unschedule_expiry(&proof, old_expiry);
+			Self::schedule_expiry(&proof, expires_at);
+
+			Self::deposit_event(RawEvent::ClaimRenewed(sender, proof, expires_at));
+		}
+
+		/// 创建多方共有存证，撤销或转移需 threshold 个所有者批准
+		#[weight = T::WeightInfo::claim_created(proof.len() as u32)
+			.saturating_add(T::DbWeight::get().writes(co_owners.len() as Weight + 1))]
+		pub fn claim_co_owned(origin, proof: Vec<u8>, co_owners: Vec<T::AccountId>, threshold: u32){
+			let sender = ensure_signed(origin)?;
+			Self::ensure_raw_proof_valid(&proof)?;
+
+			let mut owners = sp_std::vec![sender.clone()];
+			for who in co_owners {
+				if !owners.contains(&who) {
+					owners.push(who);
+				}
+			}
+			ensure!(owners.len() as u32 <= T::MaxCoOwners::get(), Error::<T>::TooManyCoOwners);
+			ensure!(threshold >= 1 && threshold <= owners.len() as u32, Error::<T>::InvalidThreshold);
+
+			Self::do_create_claim(&sender, &proof, &None, None)?;
+			for who in owners.iter().filter(|who| *who != &sender) {
+				Self::insert_owned_proof(who, &proof);
+			}
+			CoOwners::<T>::insert(&proof, CoOwnership { owners: owners.clone(), threshold });
+
+			Self::deposit_event(RawEvent::ClaimCreated(sender.clone(), proof.clone(), None));
+			Self::deposit_event(RawEvent::CoOwnedClaimCreated(sender, proof, owners, threshold));
+		}
+
+		/// 所有者批准撤销或转移，达到门限后立即执行
+		#[weight = T::WeightInfo::transfer_claim(proof.len() as u32)]
+		pub fn approve_action(origin, proof: Vec<u8>, action: ClaimAction<T::AccountId>){
+			let sender = ensure_signed(origin)?;
+
+			let co = Self::co_owners(&proof).ok_or(Error::<T>::NotCoOwnedClaim)?;
+			ensure!(co.owners.contains(&sender), Error::<T>::NotProofOwner);
+			Self::ensure_not_disputed(&proof)?;
+			let now = <frame_system::Module<T>>::block_number();
+			let mut pending = match Self::pending_approval(&proof) {
+				Some(p) if p.expires_at > now => {
+					ensure!(p.action == action, Error::<T>::ConflictingAction);
+					ensure!(!p.approvals.contains(&sender), Error::<T>::AlreadyApproved);
+					p
+				},
+				expired => {
+					if let Some(p) = expired {
+						Self::deposit_event(RawEvent::ApprovalExpired(proof.clone(), p.action));
+					}
+					PendingApproval {
+						action: action.clone(),
+						approvals: Vec::new(),
+						expires_at: now.saturating_add(T::ApprovalTimeout::get()),
+					}
+				},
+			};
+			pending.approvals.push(sender.clone());
+			let approvals = pending.approvals.len() as u32;
+
+			if approvals >= co.threshold {
+				let owner = Proofs::<T>::get(&proof).ok_or(Error::<T>::NoSuchProof)?.owner;
+				match &action {
+					ClaimAction::Revoke => Self::remove_claim(&owner, &proof, HistoryAction::Revoked),
+					ClaimAction::Transfer(dest) => Self::do_transfer(&owner, &proof, dest)?,
+				}
+				Self::deposit_event(RawEvent::ActionApproved(sender, proof.clone(), action.clone(), approvals));
+				match &action {
+					ClaimAction::Revoke =>
+						Self::deposit_event(RawEvent::ClaimRevoked(owner, proof.clone())),
+					ClaimAction::Transfer(dest) =>
+						Self::deposit_event(RawEvent::TransferClaim(owner, proof.clone(), dest.clone())),
+				}
+				Self::deposit_event(RawEvent::ActionExecuted(proof, action));
+			} else {
+				PendingApprovals::<T>::insert(&proof, pending);
+				Self::deposit_event(RawEvent::ActionApproved(sender, proof, action, approvals));
+			}
+		}
+
+		/// 清除已超时的批准，任何账号均可调用
+		#[weight = 10_000]
+		pub fn clear_expired_approval(origin, proof: Vec<u8>){
+			let _sender = ensure_signed(origin)?;
+
+			let pending = Self::pending_approval(&proof).ok_or(Error::<T>::NoPendingApproval)?;
+			ensure!(pending.expires_at <= <frame_system::Module<T>>::block_number(), Error::<T>::ApprovalNotExpired);
+			PendingApprovals::<T>::remove(&proof);
+
+			Self::deposit_event(RawEvent::ApprovalExpired(proof, pending.action));
+		}
+
+		/// 链上计算文档摘要，并校验其是否已存证，按文档长度计费
+		#[weight = T::WeightInfo::verify_document(document.len() as u32)]
+		pub fn verify_document(origin, algorithm: HashAlgorithm, document: Vec<u8>){
+			let _sender = ensure_signed(origin)?;
+
+			let proof = Self::hash_proof_key(algorithm, &algorithm.hash(&document));
+			let owner = Proofs::<T>::get(&proof).ok_or(Error::<T>::NoSuchProof)?.owner;
+
+			Self::deposit_event(RawEvent::DocumentVerified(owner, proof));
+		}
+
+		/// 对存证发起争议并质押押金，存证冻结至 ForceOrigin 处理
+		#[weight = T::DbWeight::get().reads_writes(3, 2)]
+		pub fn dispute_claim(origin, proof: Vec<u8>, reason: u32){
+			let sender = ensure_signed(origin)?;
+
+			ensure!(Proofs::<T>::contains_key(&proof), Error::<T>::NoSuchProof);
+			ensure!(!Disputes::<T>::contains_key(&proof), Error::<T>::AlreadyDisputed);
+			let deposit = T::DisputeDeposit::get();
+			T::Currency::reserve(&sender, deposit).map_err(|_| Error::<T>::InsufficientDeposit)?;
+			Disputes::<T>::insert(&proof, Dispute { disputer: sender.clone(), reason, deposit });
+
+			Self::deposit_event(RawEvent::ClaimDisputed(sender, proof, reason));
+		}
+
+		/// 处理争议：revoke 为 true 时撤销存证并退还发起方押金，否则解冻并将押金赔付给所有者，
+		/// 争议期间已到过期区块的存证随即过期
+		#[weight = T::WeightInfo::claim_revoked(proof.len() as u32)]
+		pub fn resolve_dispute(origin, proof: Vec<u8>, revoke: bool){
+			T::ForceOrigin::ensure_origin(origin)?;
+
+			let dispute = Self::dispute_of(&proof).ok_or(Error::<T>::NoSuchDispute)?;
+			let owner = Proofs::<T>::get(&proof).ok_or(Error::<T>::NoSuchProof)?.owner;
+			if revoke {
+				Self::remove_claim(&owner, &proof, HistoryAction::Revoked);
+				Self::deposit_event(RawEvent::ClaimForceRevoked(owner, proof, dispute.reason));
+			} else {
+				T::Currency::repatriate_reserved(&dispute.disputer, &owner, dispute.deposit, BalanceStatus::Free)?;
+				Disputes::<T>::remove(&proof);
+				Self::deposit_event(RawEvent::DisputeRejected(dispute.disputer, proof.clone(), dispute.reason));
+				let now = <frame_system::Module<T>>::block_number();
+				if Self::expiry_of(&proof).map_or(false, |expires_at| expires_at <= now) {
+					Self::expire_claim(&proof);
+				}
+			}
+		}
+
+		/// 不经争议直接强制撤销存证，押金退还所有者
+		#[weight = T::WeightInfo::claim_revoked(proof.len() as u32)]
+		pub fn force_revoke(origin, proof: Vec<u8>, reason: u32){
+			T::ForceOrigin::ensure_origin(origin)?;
+
+			let owner = Proofs::<T>::get(&proof).ok_or(Error::<T>::NoSuchProof)?.owner;
+			Self::remove_claim(&owner, &proof, HistoryAction::Revoked);
+
+			Self::deposit_event(RawEvent::ClaimForceRevoked(owner, proof, reason));
+		}
+
+		/// 以一个 Merkle 根锚定一批文档，树的构建见 `merkle` 模块
+		#[weight = T::WeightInfo::claim_created(32)]
+		pub fn claim_merkle_root(origin, root: [u8; 32], leaf_count: u32){
+			let sender = ensure_signed(origin)?;
+			ensure!(leaf_count > 0, Error::<T>::InvalidLeafCount);
+			ensure!(!MerkleRoots::<T>::contains_key(&root), Error::<T>::MerkleRootAlreadyClaimed);
+
+			let deposit = Self::deposit_for(&root, &None);
+			T::Currency::reserve(&sender, deposit).map_err(|_| Error::<T>::InsufficientDeposit)?;
+			MerkleRoots::<T>::insert(&root, MerkleRoot {
+				owner: sender.clone(),
+				leaf_count,
+				created_at: <frame_system::Module<T>>::block_number(),
+				deposit,
+			});
+
+			Self::deposit_event(RawEvent::MerkleRootClaimed(sender, root, leaf_count));
+		}
+
+		/// 撤销 Merkle 根并退还押金
+		#[weight = T::WeightInfo::claim_revoked(32)]
+		pub fn revoke_merkle_root(origin, root: [u8; 32]){
+			let sender = ensure_signed(origin)?;
+
+			let anchored = Self::merkle_root(&root).ok_or(Error::<T>::NoSuchMerkleRoot)?;
+			ensure!(sender==anchored.owner,Error::<T>::NotProofOwner);
+			MerkleRoots::<T>::remove(&root);
+			T::Currency::unreserve(&sender, anchored.deposit);
+
+			Self::deposit_event(RawEvent::MerkleRootRevoked(sender, root));
+		}
+
+		/// 校验文档属于已锚定的批次，按文档长度和证明步数计费
+		#[weight = T::WeightInfo::verify_inclusion(document.len() as u32, proof_path.len() as u32)]
+		pub fn verify_inclusion(origin, document: Vec<u8>, proof_path: Vec<MerkleStep>, root: [u8; 32]){
+			let _sender = ensure_signed(origin)?;
+
+			let anchored = Self::merkle_root(&root).ok_or(Error::<T>::NoSuchMerkleRoot)?;
+			ensure!(Self::is_included(&document, &proof_path, &root), Error::<T>::InvalidMerkleProof);
+
+			Self::deposit_event(RawEvent::InclusionVerified(anchored.owner, root));
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// 摘要存证在 Proofs 中的 key：算法标记 + 32 字节摘要
+	pub fn hash_proof_key(algorithm: HashAlgorithm, digest: &[u8; 32]) -> Vec<u8> {
+		(algorithm, digest).encode()
+	}
+
+	/// 是否与摘要存证的 key 同形，原始字节存证不能占用这些 key
+	pub fn is_hash_proof_key(proof: &[u8]) -> bool {
+		proof.len() == HASH_PROOF_KEY_LEN && HashAlgorithm::decode(&mut &proof[..1]).is_ok()
 	}
-}
\ No newline at end of file
+
+	/// 查询存证的 (所有者, 区块)
+	pub fn proof_owner(proof: &[u8]) -> Option<(T::AccountId, T::BlockNumber)> {
+		Proofs::<T>::get(proof).map(|info| (info.owner, info.updated_at))
+	}
+
+	/// 查询文档对应的存证 (所有者, 区块)
+	pub fn document_owner(algorithm: HashAlgorithm, document: &[u8]) -> Option<(T::AccountId, T::BlockNumber)> {
+		Self::proof_owner(&Self::hash_proof_key(algorithm, &algorithm.hash(document)))
+	}
+
+	/// 文档是否属于以 root 锚定的批次
+	pub fn is_included(document: &[u8], proof_path: &[MerkleStep], root: &[u8; 32]) -> bool {
+		MerkleRoots::<T>::get(root).map_or(false, |anchored| {
+			proof_path.len() <= merkle_depth(anchored.leaf_count)
+				&& &merkle_root_from_path(document, proof_path) == root
+		})
+	}
+
+	/// 分页查询账号拥有的存证，按存储顺序跳过 `start` 条后最多返回 `count` 条
+	pub fn proofs_of(owner: &T::AccountId, start: u32, count: u32) -> Vec<Vec<u8>> {
+		OwnedProofs::<T>::iter_prefix(owner)
+			.skip(start as usize)
+			.take(count as usize)
+			.map(|(proof, _)| proof)
+			.collect()
+	}
+
+	/// 按存证长度及编码后的元数据长度计算押金
+	pub fn deposit_for(proof: &[u8], metadata: &Option<ClaimMetadata>) -> BalanceOf<T> {
+		let metadata_len = metadata.as_ref().map_or(0, |m| m.encoded_size());
+		let len = BalanceOf::<T>::from((proof.len() + metadata_len) as u32);
+		T::ProofDepositBase::get().saturating_add(T::ProofDepositPerByte::get().saturating_mul(len))
+	}
+
+	fn do_create_claim(
+		sender: &T::AccountId,
+		proof: &[u8],
+		metadata: &Option<ClaimMetadata>,
+		expires_at: Option<T::BlockNumber>,
+	) -> Result<(), DispatchError> {
+		ensure!(!Proofs::<T>::contains_key(proof), Error::<T>::ProofAlreadyClaimed);
+		if let Some(m) = metadata {
+			Self::ensure_metadata_valid(m)?;
+		}
+		if let Some(expires_at) = expires_at {
+			Self::ensure_expiry_valid(expires_at)?;
+		}
+		let deposit = Self::deposit_for(proof, metadata);
+		T::Currency::reserve(sender, deposit).map_err(|_| Error::<T>::InsufficientDeposit)?;
+		let current_block = <frame_system::Module<T>>::block_number();
+		Proofs::<T>::insert(proof, ProofInfo {
+			owner: sender.clone(),
+			created_at: current_block,
+			updated_at: current_block,
+			deposit,
+		});
+		if let Some(m) = metadata {
+			Metadata::insert(proof, m);
+		}
+		Self::insert_owned_proof(sender, proof);
+		if let Some(expires_at) = expires_at {
+			Self::schedule_expiry(proof, expires_at);
+		}
+		Self::record_history(proof, HistoryAction::Created, sender);
+		Ok(())
+	}
+
+	/// 过期区块须在未来，且该区块的过期队列未满
+	fn ensure_expiry_valid(expires_at: T::BlockNumber) -> Result<(), DispatchError> {
+		ensure!(expires_at > <frame_system::Module<T>>::block_number(), Error::<T>::InvalidExpiry);
+		ensure!(
+			(ExpiryQueue::<T>::decode_len(expires_at).unwrap_or(0) as u32) < T::MaxExpiriesPerBlock::get(),
+			Error::<T>::TooManyExpiries
+		);
+		Ok(())
+	}
+
+	fn schedule_expiry(proof: &[u8], expires_at: T::BlockNumber) {
+		ExpiryQueue::<T>::append(expires_at, proof);
+		Expiries::<T>::insert(proof, expires_at);
+	}
+
+	fn unschedule_expiry(proof: &[u8], expires_at: T::BlockNumber) {
+		ExpiryQueue::<T>::mutate_exists(expires_at, |proofs| {
+			if let Some(list) = proofs {
+				list.retain(|p| p != proof);
+				if list.is_empty() {
+					*proofs = None;
+				}
+			}
+		});
+	}
+
+	/// 逐项执行批量操作，最后发出汇总事件
+	fn do_batch(
+		sender: &T::AccountId,
+		proofs: Vec<Vec<u8>>,
+		atomic: bool,
+		f: impl Fn(&T::AccountId, Vec<u8>) -> Result<(), DispatchError>,
+	) -> Result<(), DispatchError> {
+		with_transaction(|| {
+			let mut succeeded = 0u32;
+			let mut failed = Vec::new();
+			for (i, proof) in proofs.into_iter().enumerate() {
+				match f(sender, proof) {
+					Ok(()) => succeeded += 1,
+					Err(e) if atomic => return TransactionOutcome::Rollback(Err(e)),
+					Err(_) => failed.push(i as u32),
+				}
+			}
+			Self::deposit_event(RawEvent::BatchCompleted(sender.clone(), succeeded, failed));
+			TransactionOutcome::Commit(Ok(()))
+		})
+	}
+
+	/// 转移存证，押金随存证转移给新的所有者；多方共有存证转移后归 to 单独所有。
+	/// 押金以保留余额转入，接收方账号须已存在
+	fn do_transfer(from: &T::AccountId, proof: &[u8], to: &T::AccountId) -> Result<(), DispatchError> {
+		let mut info = Proofs::<T>::get(proof).ok_or(Error::<T>::NoSuchProof)?;
+		Self::ensure_not_disputed(proof)?;
+		ensure!(!T::Currency::total_balance(to).is_zero(), Error::<T>::RecipientNotFound);
+		T::Currency::repatriate_reserved(from, to, info.deposit, BalanceStatus::Reserved)?;
+		info.owner = to.clone();
+		info.updated_at = <frame_system::Module<T>>::block_number();
+		Proofs::<T>::insert(proof, info);
+		PendingOffers::<T>::remove(proof);
+		Self::remove_co_owners(from, proof);
+		Self::remove_owned_proof(from, proof);
+		Self::insert_owned_proof(to, proof);
+		Self::record_history(proof, HistoryAction::Transferred, to);
+		Ok(())
+	}
+
+	/// 删除共有信息及其他所有者的索引
+	fn remove_co_owners(owner: &T::AccountId, proof: &[u8]) {
+		PendingApprovals::<T>::remove(proof);
+		if let Some(co) = CoOwners::<T>::take(proof) {
+			for who in co.owners.iter().filter(|who| *who != owner) {
+				Self::remove_owned_proof(who, proof);
+			}
+		}
+	}
+
+	/// 删除存证及其所有附属数据，押金扣除 HistoryDeposit 后退还
+	fn remove_claim(owner: &T::AccountId, proof: &[u8], action: HistoryAction) {
+		let deposit = Proofs::<T>::take(proof).map(|info| info.deposit).unwrap_or_else(Zero::zero);
+		PendingOffers::<T>::remove(proof);
+		Self::remove_co_owners(owner, proof);
+		Metadata::remove(proof);
+		Self::remove_owned_proof(owner, proof);
+		// HistoryCount 不随存证删除，扣下的部分直接销毁
+		let history_fee = T::HistoryDeposit::get().min(deposit);
+		let _ = T::Currency::slash_reserved(owner, history_fee);
+		T::Currency::unreserve(owner, deposit - history_fee);
+		if let Some(expires_at) = Expiries::<T>::take(proof) {
+			Self::unschedule_expiry(proof, expires_at);
+		}
+		// 存证已不存在，争议自然成立，退还发起方押金
+		if let Some(dispute) = Disputes::<T>::take(proof) {
+			T::Currency::unreserve(&dispute.disputer, dispute.deposit);
+		}
+		Self::record_history(proof, action, owner);
+	}
+
+	/// 追加一条存证历史到链下索引，需以 --enable-offchain-indexing 启动节点才会落盘
+	fn record_history(proof: &[u8], action: HistoryAction, owner: &T::AccountId) {
+		let index = HistoryCount::mutate(proof, |count| {
+			let index = *count;
+			*count = count.saturating_add(1);
+			index
+		});
+		let record = HistoryRecord {
+			action,
+			owner: owner.clone(),
+			block: <frame_system::Module<T>>::block_number(),
+		};
+		sp_io::offchain_index::set(&history_key(proof, index), &record.encode());
+	}
+
+	/// 争议未结的存证暂不过期，保留 Expiries 由 resolve_dispute 驳回时处理
+	fn expire_claim(proof: &[u8]) {
+		let owner = match Proofs::<T>::get(proof) {
+			Some(info) => info.owner,
+			None => return,
+		};
+		if Disputes::<T>::contains_key(proof) {
+			return;
+		}
+		Self::remove_claim(&owner, proof, HistoryAction::Expired);
+		Self::deposit_event(RawEvent::ClaimExpired(owner, proof.to_vec()));
+	}
+
+	fn insert_owned_proof(owner: &T::AccountId, proof: &[u8]) {
+		OwnedProofs::<T>::insert(owner, proof, ());
+		OwnedProofsCount::<T>::mutate(owner, |count| *count = count.saturating_add(1));
+	}
+
+	fn remove_owned_proof(owner: &T::AccountId, proof: &[u8]) {
+		OwnedProofs::<T>::remove(owner, proof);
+		OwnedProofsCount::<T>::mutate_exists(owner, |count| {
+			*count = count.map(|c| c.saturating_sub(1)).filter(|c| *c > 0);
+		});
+	}
+
+	/// 争议中的存证不能被所有者撤销、转移或修改元数据
+	fn ensure_not_disputed(proof: &[u8]) -> Result<(), DispatchError> {
+		ensure!(!Disputes::<T>::contains_key(proof), Error::<T>::ProofDisputed);
+		Ok(())
+	}
+
+	/// 原始字节存证的长度限制，且不能占用摘要存证的 key
+	fn ensure_raw_proof_valid(proof: &[u8]) -> Result<(), DispatchError> {
+		ensure!(!Self::is_hash_proof_key(proof), Error::<T>::ReservedProofKey);
+		ensure!(proof.len() <= T::MaxProofLength::get(), Error::<T>::ProofTooLong);
+		Ok(())
+	}
+
+	fn ensure_metadata_valid(metadata: &ClaimMetadata) -> Result<(), DispatchError> {
+		let max = T::MaxMetadataLength::get();
+		ensure!(metadata.description.len() <= max, Error::<T>::MetadataTooLong);
+		ensure!(metadata.content_type.len() <= max, Error::<T>::MetadataTooLong);
+		ensure!(metadata.uri.len() <= max, Error::<T>::MetadataTooLong);
+		Ok(())
+	}
+}
@@ -0,0 +1,65 @@
+//! 链下构建 Merkle 树和包含证明，仅在 std 下可用
+//!
+//! 叶子与节点的哈希规则见 `merkle_leaf` / `merkle_parent`，
+//! 奇数个节点时最后一个直接提升到上一层，不与自身配对。
+
+use crate::{MerkleStep, merkle_leaf, merkle_parent};
+
+/// 按层保存的 Merkle 树，layers[0] 为叶子层，最后一层为根
+#[derive(Clone, Debug)]
+pub struct MerkleTree {
+	layers: Vec<Vec<[u8; 32]>>,
+}
+
+impl MerkleTree {
+	/// 由文档构建 Merkle 树，文档为空时返回 None
+	pub fn new<D: AsRef<[u8]>>(documents: &[D]) -> Option<Self> {
+		if documents.is_empty() {
+			return None;
+		}
+		let mut layers = vec![documents.iter().map(|d| merkle_leaf(d.as_ref())).collect::<Vec<_>>()];
+		while layers[layers.len() - 1].len() > 1 {
+			let next = layers[layers.len() - 1]
+				.chunks(2)
+				.map(|pair| match pair {
+					[left, right] => merkle_parent(left, right),
+					[single] => *single,
+					_ => unreachable!(),
+				})
+				.collect();
+			layers.push(next);
+		}
+		Some(MerkleTree { layers })
+	}
+
+	/// 树根，即 claim_merkle_root 的 root 参数
+	pub fn root(&self) -> [u8; 32] {
+		self.layers[self.layers.len() - 1][0]
+	}
+
+	/// 叶子数量，即 claim_merkle_root 的 leaf_count 参数
+	pub fn leaf_count(&self) -> u32 {
+		self.layers[0].len() as u32
+	}
+
+	/// 第 index 个文档的包含证明，自叶子向根排列
+	pub fn proof(&self, index: usize) -> Option<Vec<MerkleStep>> {
+		if index >= self.layers[0].len() {
+			return None;
+		}
+		let mut path = Vec::new();
+		let mut index = index;
+		for layer in &self.layers[..self.layers.len() - 1] {
+			let sibling = index ^ 1;
+			if sibling < layer.len() {
+				path.push(if sibling < index {
+					MerkleStep::Left(layer[sibling])
+				} else {
+					MerkleStep::Right(layer[sibling])
+				});
+			}
+			index /= 2;
+		}
+		Some(path)
+	}
+}
@@ -0,0 +1,39 @@
+//! 存储迁移
+
+use super::*;
+use frame_support::{
+	StorageValue, IterableStorageMap, Blake2_128Concat, StorageHasher,
+	storage::migration::{take_storage_value, remove_storage_prefix},
+};
+use sp_std::cell::Cell;
+
+/// decl_storage 中声明的模块前缀
+const MODULE: &[u8] = b"PoeModule";
+
+/// V1 -> V2：Proofs 的值由 (AccountId, BlockNumber) 转换为 ProofInfo，
+/// 同时并入 V1 中单独存放的 ClaimCreatedAt 与 ProofDeposits，并为已有存证建立 OwnedProofs 索引
+pub fn migrate_to_v2<T: Trait>() -> Weight {
+	if StorageVersion::get() != Releases::V1 {
+		return T::DbWeight::get().reads(1);
+	}
+
+	let translated = Cell::new(0 as Weight);
+	Proofs::<T>::translate::<(T::AccountId, T::BlockNumber), _>(|proof, (owner, updated_at)| {
+		translated.set(translated.get() + 1);
+		let hash = Blake2_128Concat::hash(&proof.encode());
+		let created_at = take_storage_value::<T::BlockNumber>(MODULE, b"ClaimCreatedAt", &hash)
+			.unwrap_or(updated_at);
+		let deposit = take_storage_value::<BalanceOf<T>>(MODULE, b"ProofDeposits", &hash)
+			.unwrap_or_else(Zero::zero);
+		Module::<T>::insert_owned_proof(&owner, &proof);
+		Some(ProofInfo { owner, created_at, updated_at, deposit })
+	});
+	// 清理没有对应存证的残留数据
+	remove_storage_prefix(MODULE, b"ClaimCreatedAt", &[]);
+	remove_storage_prefix(MODULE, b"ProofDeposits", &[]);
+	StorageVersion::put(Releases::V2);
+
+	let n = translated.get();
+	// 每个存证：Proofs、两项旧存储、OwnedProofsCount 读取，另写入 OwnedProofs
+	T::DbWeight::get().reads_writes(1 + 4 * n, 1 + 5 * n)
+}
@@ -1,6 +1,9 @@
-use crate::{Module, Trait};
+use crate::{Module, Trait, GenesisConfig, Proofs};
+use codec::Encode;
 use sp_core::H256;
-use frame_support::{impl_outer_origin, parameter_types, weights::Weight};
+use frame_support::{impl_outer_origin, parameter_types, weights::Weight, traits::OnInitialize,
+	StorageMap, Blake2_128Concat, StorageHasher, storage::migration::put_storage_value,
+};
 use sp_runtime::{
 	traits::{BlakeTwo256, IdentityLookup}, testing::Header, Perbill,
 };
@@ -20,6 +23,16 @@ parameter_types! {
 	pub const MaximumBlockLength: u32 = 2 * 1024;
 	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
 	pub const MaxProofLength: usize = 10;
+	pub const MaxMetadataLength: usize = 16;
+	pub const ExistentialDeposit: u64 = 1;
+	pub const ProofDepositBase: u64 = 10;
+	pub const ProofDepositPerByte: u64 = 1;
+	pub const MaxExpiriesPerBlock: u32 = 2;
+	pub const MaxBatchSize: u32 = 4;
+	pub const MaxCoOwners: u32 = 3;
+	pub const ApprovalTimeout: u64 = 5;
+	pub const DisputeDeposit: u64 = 5;
+	pub const HistoryDeposit: u64 = 2;
 }
 
 impl system::Trait for Test {
@@ -44,20 +57,73 @@ impl system::Trait for Test {
 	type AvailableBlockRatio = AvailableBlockRatio;
 	type Version = ();
 	type PalletInfo = ();
-	type AccountData = ();
+	type AccountData = pallet_balances::AccountData<u64>;
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
 	type SystemWeightInfo = ();
 }
 
+impl pallet_balances::Trait for Test {
+	type Balance = u64;
+	type MaxLocks = ();
+	type Event = ();
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
 impl Trait for Test {
 	type Event = ();
 	type MaxProofLength = MaxProofLength;
+	type MaxMetadataLength = MaxMetadataLength;
+	type Currency = Balances;
+	type ProofDepositBase = ProofDepositBase;
+	type ProofDepositPerByte = ProofDepositPerByte;
+	type MaxExpiriesPerBlock = MaxExpiriesPerBlock;
+	type MaxBatchSize = MaxBatchSize;
+	type MaxCoOwners = MaxCoOwners;
+	type ApprovalTimeout = ApprovalTimeout;
+	type ForceOrigin = system::EnsureRoot<u64>;
+	type DisputeDeposit = DisputeDeposit;
+	type HistoryDeposit = HistoryDeposit;
+	type WeightInfo = ();
 }
 
 pub type PoeModule = Module<Test>;
+pub type System = system::Module<Test>;
+pub type Balances = pallet_balances::Module<Test>;
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-	system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(1, 1000), (2, 1000), (3, 5)],
+	}.assimilate_storage(&mut t).unwrap();
+	GenesisConfig::<Test>::default().assimilate_storage(&mut t).unwrap();
+	t.into()
+}
+
+/// 构造 V1 存储结构的链状态：proof => (owner, block)，以及 V1 中单独存放的创建区块和押金
+pub fn new_test_ext_v1(proofs: Vec<(Vec<u8>, u64, u64, Option<(u64, u64)>)>) -> sp_io::TestExternalities {
+	let t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	let mut ext: sp_io::TestExternalities = t.into();
+	ext.execute_with(|| {
+		for (proof, owner, block, extra) in proofs {
+			sp_io::storage::set(&Proofs::<Test>::hashed_key_for(&proof), &(owner, block).encode());
+			if let Some((created_at, deposit)) = extra {
+				let hash = Blake2_128Concat::hash(&proof.encode());
+				put_storage_value(b"PoeModule", b"ClaimCreatedAt", &hash, created_at);
+				put_storage_value(b"PoeModule", b"ProofDeposits", &hash, deposit);
+			}
+		}
+	});
+	ext
+}
+
+pub fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		System::set_block_number(System::block_number() + 1);
+		PoeModule::on_initialize(System::block_number());
+	}
 }
@@ -1,13 +1,14 @@
 use crate::{Error, mock::*};
-use frame_support::{assert_ok, assert_noop};
+use frame_support::{assert_ok, assert_noop, StorageValue, StorageHasher, traits::{OnRuntimeUpgrade, OnInitialize}};
 use super::*;
+use sp_core::offchain::OffchainStorage;
 /// 创建存证
 #[test]
 fn create_claim_works(){
     new_test_ext().execute_with(||{
         let claim = vec![0, 1];
-        assert_ok!(PoeModule::claim_created(Origin::signed(1), claim.clone())); // 断言运行结果
-        assert_eq!(Proofs::<Test>::get(&claim), (1, frame_system::Module::<Test>::block_number()));
+        assert_ok!(PoeModule::claim_created(Origin::signed(1), claim.clone(), None, None)); // 断言运行结果
+        assert_eq!(PoeModule::proof_owner(&claim), Some((1, frame_system::Module::<Test>::block_number())));
     })
 }
 /// 创建的存证已存在
@@ -16,9 +17,9 @@ fn create_claim_failed_when_claim_already_exist(){
     new_test_ext().execute_with(||{
 
         let claim = vec![0, 1];
-        let _ = PoeModule::claim_created(Origin::signed(1), claim.clone());
+        let _ = PoeModule::claim_created(Origin::signed(1), claim.clone(), None, None);
         assert_noop!( // 断言运行结果为ProofAlreadyClaimed
-            PoeModule::claim_created(Origin::signed(1), claim.clone()),
+            PoeModule::claim_created(Origin::signed(1), claim.clone(), None, None),
             Error::<Test>::ProofAlreadyClaimed
         );
     })
@@ -29,7 +30,7 @@ fn create_claim_failed_when_claim_already_exist(){
 fn revoke_claim_works(){
     new_test_ext().execute_with(||{
         let claim = vec![0, 1];
-        let _ = PoeModule::claim_created(Origin::signed(1), claim.clone());
+        let _ = PoeModule::claim_created(Origin::signed(1), claim.clone(), None, None);
         assert_ok!(PoeModule::claim_revoked(Origin::signed(1), claim.clone())); // 断言运行结果
     })
 }
@@ -49,7 +50,7 @@ fn revoke_claim_failed_when_claim_is_not_exist(){
 fn revoke_claim_failed_when_is_not_claim_owner(){
     new_test_ext().execute_with(||{
         let claim = vec![0, 1];
-        let _ = PoeModule::claim_created(Origin::signed(1), claim.clone());
+        let _ = PoeModule::claim_created(Origin::signed(1), claim.clone(), None, None);
         assert_noop!( // 断言运行结果为NotProofOwner
             PoeModule::claim_revoked(Origin::signed(2), claim.clone()),
             Error::<Test>::NotProofOwner
@@ -65,7 +66,7 @@ fn transfer_claim_works(){
             PoeModule::transfer_claim(Origin::signed(1), claim.clone(), 2),
             Error::<Test>::NoSuchProof
         );
-        let _ = PoeModule::claim_created(Origin::signed(1), claim.clone());
+        let _ = PoeModule::claim_created(Origin::signed(1), claim.clone(), None, None);
         assert_ok!(PoeModule::transfer_claim(Origin::signed(1), claim.clone(), 2)); // 断言运行结果，成功
         assert_noop!( // 非拥有者
             PoeModule::transfer_claim(Origin::signed(1), claim.clone(), 2),
@@ -79,11 +80,907 @@ fn transfer_claim_works(){
 fn create_claim_failed_when_it_is_too_long(){
     new_test_ext().execute_with(||{
         let claim1 = vec![0, 1, 2, 3, 4, 5, 6 ,7 ,8 ,9];
-        assert_ok!(PoeModule::claim_created(Origin::signed(1), claim1.clone())); // 断言运行结果
+        assert_ok!(PoeModule::claim_created(Origin::signed(1), claim1.clone(), None, None)); // 断言运行结果
         let claim2 = vec![0, 1, 2, 3, 4, 5, 6 ,7 ,8 ,9, 10];
         assert_noop!(
-                PoeModule::claim_created(Origin::signed(1), claim2.clone()),
+                PoeModule::claim_created(Origin::signed(1), claim2.clone(), None, None),
                 Error::<Test>::ProofTooLong
             );
     })
-}
\ No newline at end of file
+}
+/// 摘要存证
+#[test]
+fn claim_hash_works(){
+    new_test_ext().execute_with(||{
+        let document = b"contract".to_vec();
+        let digest = HashAlgorithm::Sha2_256.hash(&document);
+        assert_ok!(PoeModule::claim_hash(Origin::signed(1), HashAlgorithm::Sha2_256, digest, None, None));
+        let proof = PoeModule::hash_proof_key(HashAlgorithm::Sha2_256, &digest);
+        assert_eq!(PoeModule::proof_owner(&proof), Some((1, frame_system::Module::<Test>::block_number())));
+        assert_noop!( // 同一摘要不能重复存证
+            PoeModule::claim_hash(Origin::signed(2), HashAlgorithm::Sha2_256, digest, None, None),
+            Error::<Test>::ProofAlreadyClaimed
+        );
+        // 不同算法互不冲突
+        let digest = HashAlgorithm::Blake2_256.hash(&document);
+        assert_ok!(PoeModule::claim_hash(Origin::signed(2), HashAlgorithm::Blake2_256, digest, None, None));
+    })
+}
+
+/// 原始字节存证不能占用摘要存证的 key
+#[test]
+fn raw_claim_cannot_squat_hash_key(){
+    new_test_ext().execute_with(||{
+        let digest = HashAlgorithm::Sha2_256.hash(b"contract");
+        let key = PoeModule::hash_proof_key(HashAlgorithm::Sha2_256, &digest);
+        assert_eq!(key.len(), HASH_PROOF_KEY_LEN);
+        assert_noop!(
+            PoeModule::claim_created(Origin::signed(2), key.clone(), None, None),
+            Error::<Test>::ReservedProofKey
+        );
+        assert_noop!(
+            PoeModule::claim_co_owned(Origin::signed(2), key.clone(), vec![3], 1),
+            Error::<Test>::ReservedProofKey
+        );
+        assert_ok!(PoeModule::claim_hash(Origin::signed(1), HashAlgorithm::Sha2_256, digest, None, None));
+        assert_eq!(PoeModule::document_owner(HashAlgorithm::Sha2_256, b"contract").map(|(owner, _)| owner), Some(1));
+        // 长度相同但算法标记无效的字节仍可作为原始存证
+        let mut raw = key;
+        raw[0] = 0xff;
+        assert!(!PoeModule::is_hash_proof_key(&raw));
+    })
+}
+
+/// 链上校验文档
+#[test]
+fn verify_document_works(){
+    new_test_ext().execute_with(||{
+        let document = b"contract".to_vec();
+        assert_noop!(
+            PoeModule::verify_document(Origin::signed(2), HashAlgorithm::Keccak256, document.clone()),
+            Error::<Test>::NoSuchProof
+        );
+        let digest = HashAlgorithm::Keccak256.hash(&document);
+        assert_ok!(PoeModule::claim_hash(Origin::signed(1), HashAlgorithm::Keccak256, digest, None, None));
+        assert_ok!(PoeModule::verify_document(Origin::signed(2), HashAlgorithm::Keccak256, document.clone()));
+        assert_eq!(PoeModule::document_owner(HashAlgorithm::Keccak256, &document).map(|(owner, _)| owner), Some(1));
+        assert_eq!(PoeModule::document_owner(HashAlgorithm::Sha2_256, &document), None);
+    })
+}
+
+/// 存证元数据
+#[test]
+fn claim_metadata_works(){
+    new_test_ext().execute_with(||{
+        let claim = vec![0, 1];
+        let metadata = ClaimMetadata {
+            description: b"lease".to_vec(),
+            content_type: b"application/pdf".to_vec(),
+            uri: b"ipfs://Qm".to_vec(),
+        };
+        assert_ok!(PoeModule::claim_created(Origin::signed(1), claim.clone(), Some(metadata.clone()), None));
+        assert_eq!(PoeModule::metadata(&claim), Some(metadata.clone()));
+        assert_noop!( // 非拥有者不能更新
+            PoeModule::set_metadata(Origin::signed(2), claim.clone(), None),
+            Error::<Test>::NotProofOwner
+        );
+        let updated = ClaimMetadata { uri: b"https://a.b".to_vec(), ..metadata };
+        assert_ok!(PoeModule::set_metadata(Origin::signed(1), claim.clone(), Some(updated.clone())));
+        assert_eq!(PoeModule::metadata(&claim), Some(updated));
+        assert_ok!(PoeModule::claim_revoked(Origin::signed(1), claim.clone()));
+        assert_eq!(PoeModule::metadata(&claim), None); // 撤销后删除
+    })
+}
+
+/// 元数据计入押金，更新元数据时补足或退还差额
+#[test]
+fn metadata_deposit_follows_metadata_length(){
+    new_test_ext().execute_with(||{
+        let claim = vec![0, 1];
+        let metadata = ClaimMetadata { description: b"abc".to_vec(), ..Default::default() };
+        assert_eq!(metadata.encoded_size(), 6);
+        assert_ok!(PoeModule::claim_created(Origin::signed(1), claim.clone(), Some(metadata), None));
+        assert_eq!(Balances::reserved_balance(1), 10 + 2 + 6);
+        assert_eq!(PoeModule::proofs(&claim).unwrap().deposit, 18);
+
+        assert_ok!(PoeModule::set_metadata(Origin::signed(1), claim.clone(), None));
+        assert_eq!(Balances::reserved_balance(1), 12);
+        assert_eq!(PoeModule::proofs(&claim).unwrap().deposit, 12);
+
+        let larger = ClaimMetadata { description: vec![0; 16], ..Default::default() };
+        Balances::make_free_balance_be(&1, 10);
+        assert_noop!(
+            PoeModule::set_metadata(Origin::signed(1), claim.clone(), Some(larger.clone())),
+            Error::<Test>::InsufficientDeposit
+        );
+        Balances::make_free_balance_be(&1, 100);
+        assert_ok!(PoeModule::set_metadata(Origin::signed(1), claim.clone(), Some(larger)));
+        assert_eq!(Balances::reserved_balance(1), 12 + 19);
+    })
+}
+
+/// 元数据长度限制
+#[test]
+fn claim_metadata_failed_when_it_is_too_long(){
+    new_test_ext().execute_with(||{
+        let claim = vec![0, 1];
+        let metadata = ClaimMetadata { description: vec![0; 17], ..Default::default() };
+        assert_noop!(
+            PoeModule::claim_created(Origin::signed(1), claim.clone(), Some(metadata), None),
+            Error::<Test>::MetadataTooLong
+        );
+    })
+}
+
+/// 账号存证索引
+#[test]
+fn owned_proofs_index_works(){
+    new_test_ext().execute_with(||{
+        assert_ok!(PoeModule::claim_created(Origin::signed(1), vec![0], None, None));
+        assert_ok!(PoeModule::claim_created(Origin::signed(1), vec![1], None, None));
+        assert_ok!(PoeModule::claim_created(Origin::signed(1), vec![2], None, None));
+        assert_eq!(PoeModule::owned_proofs_count(1), 3);
+        assert_eq!(OwnedProofs::<Test>::contains_key(1, vec![1]), true);
+
+        assert_ok!(PoeModule::transfer_claim(Origin::signed(1), vec![1], 2)); // 转移
+        assert_eq!(OwnedProofs::<Test>::contains_key(1, vec![1]), false);
+        assert_eq!(OwnedProofs::<Test>::contains_key(2, vec![1]), true);
+        assert_eq!(PoeModule::owned_proofs_count(1), 2);
+        assert_eq!(PoeModule::owned_proofs_count(2), 1);
+
+        assert_ok!(PoeModule::claim_revoked(Origin::signed(2), vec![1])); // 撤销
+        assert_eq!(OwnedProofs::<Test>::contains_key(2, vec![1]), false);
+        assert_eq!(OwnedProofsCount::<Test>::contains_key(2), false);
+    })
+}
+
+/// 分页查询账号存证
+#[test]
+fn proofs_of_paginates(){
+    new_test_ext().execute_with(||{
+        for i in 0..5u8 {
+            assert_ok!(PoeModule::claim_created(Origin::signed(1), vec![i], None, None));
+        }
+        let first = PoeModule::proofs_of(&1, 0, 2);
+        let second = PoeModule::proofs_of(&1, 2, 2);
+        let last = PoeModule::proofs_of(&1, 4, 2);
+        assert_eq!((first.len(), second.len(), last.len()), (2, 2, 1));
+        let mut all = [first, second, last].concat();
+        all.sort();
+        assert_eq!(all, vec![vec![0], vec![1], vec![2], vec![3], vec![4]]);
+        assert!(PoeModule::proofs_of(&2, 0, 10).is_empty());
+    })
+}
+
+/// 创建存证质押押金，撤销后扣除 HistoryDeposit 退还
+#[test]
+fn claim_deposit_reserved_and_unreserved(){
+    new_test_ext().execute_with(||{
+        let claim = vec![0, 1, 2];
+        assert_eq!(PoeModule::deposit_for(&claim, &None), 13); // 10 + 3 * 1
+        assert_ok!(PoeModule::claim_created(Origin::signed(1), claim.clone(), None, None));
+        assert_eq!(Balances::reserved_balance(1), 13);
+        assert_eq!(Balances::free_balance(1), 987);
+        assert_ok!(PoeModule::claim_revoked(Origin::signed(1), claim.clone()));
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Balances::free_balance(1), 1000 - 2);
+    })
+}
+
+/// 转移存证时押金随之转移
+#[test]
+fn claim_deposit_moves_on_transfer(){
+    new_test_ext().execute_with(||{
+        let claim = vec![0, 1];
+        assert_ok!(PoeModule::claim_created(Origin::signed(1), claim.clone(), None, None));
+        assert_ok!(PoeModule::transfer_claim(Origin::signed(1), claim.clone(), 2));
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Balances::free_balance(1), 988);
+        assert_eq!(Balances::reserved_balance(2), 12);
+        assert_ok!(PoeModule::claim_revoked(Origin::signed(2), claim.clone()));
+        assert_eq!(Balances::free_balance(2), 1012 - 2);
+    })
+}
+
+/// 接收方账号不存在时无法接收押金，转移失败
+#[test]
+fn transfer_claim_failed_when_recipient_has_no_account(){
+    new_test_ext().execute_with(||{
+        let claim = vec![0, 1];
+        assert_ok!(PoeModule::claim_created(Origin::signed(1), claim.clone(), None, None));
+        assert_noop!(
+            PoeModule::transfer_claim(Origin::signed(1), claim.clone(), 4),
+            Error::<Test>::RecipientNotFound
+        );
+        assert_ok!(PoeModule::offer_claim(Origin::signed(1), claim.clone(), 4));
+        assert_noop!(
+            PoeModule::accept_claim(Origin::signed(4), claim.clone()),
+            Error::<Test>::RecipientNotFound
+        );
+        // 账号有余额后即可接受
+        Balances::make_free_balance_be(&4, 100);
+        assert_ok!(PoeModule::accept_claim(Origin::signed(4), claim.clone()));
+        assert_eq!(PoeModule::proofs(&claim).map(|p| p.owner), Some(4));
+        assert_eq!(Balances::reserved_balance(4), 12);
+    })
+}
+
+/// 多方共有存证转移给不存在的账号时，批准不生效
+#[test]
+fn co_owned_transfer_failed_when_recipient_has_no_account(){
+    new_test_ext().execute_with(||{
+        let claim = vec![0, 1];
+        assert_ok!(PoeModule::claim_co_owned(Origin::signed(1), claim.clone(), vec![2], 2));
+        assert_ok!(PoeModule::approve_action(Origin::signed(1), claim.clone(), ClaimAction::Transfer(4)));
+        assert_noop!(
+            PoeModule::approve_action(Origin::signed(2), claim.clone(), ClaimAction::Transfer(4)),
+            Error::<Test>::RecipientNotFound
+        );
+        assert_eq!(PoeModule::proofs(&claim).map(|p| p.owner), Some(1));
+    })
+}
+
+/// 余额不足以支付押金
+#[test]
+fn create_claim_failed_when_deposit_is_insufficient(){
+    new_test_ext().execute_with(||{
+        assert_noop!(
+            PoeModule::claim_created(Origin::signed(3), vec![0, 1], None, None),
+            Error::<Test>::InsufficientDeposit
+        );
+    })
+}
+
+/// 存证到期后自动移除，押金退还
+#[test]
+fn claim_expires_in_on_initialize(){
+    new_test_ext().execute_with(||{
+        let claim = vec![0, 1];
+        assert_ok!(PoeModule::claim_created(Origin::signed(1), claim.clone(), None, Some(3)));
+        assert_eq!(PoeModule::expiry_of(&claim), Some(3));
+        assert_eq!(PoeModule::expiring_at(3), vec![claim.clone()]);
+        run_to_block(2);
+        assert_eq!(Proofs::<Test>::contains_key(&claim), true);
+        run_to_block(3);
+        assert_eq!(Proofs::<Test>::contains_key(&claim), false);
+        assert_eq!(PoeModule::expiry_of(&claim), None);
+        assert_eq!(ExpiryQueue::<Test>::contains_key(3), false);
+        assert_eq!(OwnedProofs::<Test>::contains_key(1, &claim), false);
+        assert_eq!(Balances::reserved_balance(1), 0);
+    })
+}
+
+/// on_initialize 按实际到期的存证数量计算权重
+#[test]
+fn expiry_weight_counts_expired_claims(){
+    new_test_ext().execute_with(||{
+        assert_ok!(PoeModule::claim_created(Origin::signed(1), vec![0], None, Some(3)));
+        assert_ok!(PoeModule::claim_created(Origin::signed(1), vec![1], None, Some(3)));
+        assert_eq!(PoeModule::on_initialize(2), <() as WeightInfo>::on_initialize(0));
+        assert_eq!(PoeModule::on_initialize(3), <() as WeightInfo>::on_initialize(2));
+        assert!(<() as WeightInfo>::on_initialize(2) > <() as WeightInfo>::on_initialize(1));
+    })
+}
+
+/// 过期区块校验
+#[test]
+fn create_claim_failed_when_expiry_is_invalid(){
+    new_test_ext().execute_with(||{
+        run_to_block(5);
+        assert_noop!(
+            PoeModule::claim_created(Origin::signed(1), vec![0], None, Some(5)),
+            Error::<Test>::InvalidExpiry
+        );
+        assert_ok!(PoeModule::claim_created(Origin::signed(1), vec![0], None, Some(8)));
+        assert_ok!(PoeModule::claim_created(Origin::signed(1), vec![1], None, Some(8)));
+        assert_noop!( // 每个区块最多过期2个
+            PoeModule::claim_created(Origin::signed(1), vec![2], None, Some(8)),
+            Error::<Test>::TooManyExpiries
+        );
+    })
+}
+
+/// 续期存证
+#[test]
+fn renew_claim_works(){
+    new_test_ext().execute_with(||{
+        let claim = vec![0, 1];
+        assert_noop!(
+            PoeModule::renew_claim(Origin::signed(1), claim.clone(), 5),
+            Error::<Test>::NoSuchProof
+        );
+        assert_ok!(PoeModule::claim_created(Origin::signed(1), claim.clone(), None, None));
+        assert_noop!( // 永久存证无需续期
+            PoeModule::renew_claim(Origin::signed(1), claim.clone(), 5),
+            Error::<Test>::ClaimNotExpiring
+        );
+        assert_ok!(PoeModule::claim_revoked(Origin::signed(1), claim.clone()));
+
+        assert_ok!(PoeModule::claim_created(Origin::signed(1), claim.clone(), None, Some(3)));
+        assert_noop!(
+            PoeModule::renew_claim(Origin::signed(2), claim.clone(), 5),
+            Error::<Test>::NotProofOwner
+        );
+        assert_noop!( // 只能延长
+            PoeModule::renew_claim(Origin::signed(1), claim.clone(), 2),
+            Error::<Test>::InvalidExpiry
+        );
+        assert_ok!(PoeModule::renew_claim(Origin::signed(1), claim.clone(), 5));
+        assert_eq!(ExpiryQueue::<Test>::contains_key(3), false);
+        run_to_block(4);
+        assert_eq!(Proofs::<Test>::contains_key(&claim), true);
+        run_to_block(5);
+        assert_eq!(Proofs::<Test>::contains_key(&claim), false);
+    })
+}
+
+/// 撤销存证时从过期队列移除
+#[test]
+fn revoke_claim_unschedules_expiry(){
+    new_test_ext().execute_with(||{
+        let claim = vec![0, 1];
+        assert_ok!(PoeModule::claim_created(Origin::signed(1), claim.clone(), None, Some(3)));
+        assert_ok!(PoeModule::claim_revoked(Origin::signed(1), claim.clone()));
+        assert_eq!(ExpiryQueue::<Test>::contains_key(3), false);
+        assert_eq!(PoeModule::expiry_of(&claim), None);
+    })
+}
+
+/// 两步转移：发起并接受
+#[test]
+fn offer_and_accept_claim_works(){
+    new_test_ext().execute_with(||{
+        let claim = vec![0, 1];
+        assert_ok!(PoeModule::claim_created(Origin::signed(1), claim.clone(), None, None));
+        run_to_block(3);
+        assert_noop!(
+            PoeModule::offer_claim(Origin::signed(2), claim.clone(), 2),
+            Error::<Test>::NotProofOwner
+        );
+        assert_ok!(PoeModule::offer_claim(Origin::signed(1), claim.clone(), 2));
+        assert_eq!(PoeModule::pending_offer(&claim), Some(2));
+        assert_eq!(PoeModule::proofs(&claim).unwrap().owner, 1); // 接受前所有者不变
+        assert_noop!(
+            PoeModule::accept_claim(Origin::signed(3), claim.clone()),
+            Error::<Test>::NotOfferRecipient
+        );
+        assert_ok!(PoeModule::accept_claim(Origin::signed(2), claim.clone()));
+        assert_eq!(PoeModule::proofs(&claim), Some(ProofInfo { owner: 2, created_at: 0, updated_at: 3, deposit: 12 })); // 创建区块保持不变
+        assert_eq!(PoeModule::pending_offer(&claim), None);
+        assert_eq!(Balances::reserved_balance(2), 12);
+        assert_noop!(
+            PoeModule::accept_claim(Origin::signed(2), claim.clone()),
+            Error::<Test>::NoPendingOffer
+        );
+    })
+}
+
+/// 撤回转移
+#[test]
+fn cancel_offer_works(){
+    new_test_ext().execute_with(||{
+        let claim = vec![0, 1];
+        assert_ok!(PoeModule::claim_created(Origin::signed(1), claim.clone(), None, None));
+        assert_noop!(
+            PoeModule::cancel_offer(Origin::signed(1), claim.clone()),
+            Error::<Test>::NoPendingOffer
+        );
+        assert_ok!(PoeModule::offer_claim(Origin::signed(1), claim.clone(), 2));
+        assert_ok!(PoeModule::cancel_offer(Origin::signed(1), claim.clone()));
+        assert_noop!(
+            PoeModule::accept_claim(Origin::signed(2), claim.clone()),
+            Error::<Test>::NoPendingOffer
+        );
+        // 撤销存证同时清除待接受的转移
+        assert_ok!(PoeModule::offer_claim(Origin::signed(1), claim.clone(), 2));
+        assert_ok!(PoeModule::claim_revoked(Origin::signed(1), claim.clone()));
+        assert_eq!(PoeModule::pending_offer(&claim), None);
+    })
+}
+
+/// 批量存证，跳过失败项
+#[test]
+fn claim_batch_best_effort(){
+    new_test_ext().execute_with(||{
+        assert_ok!(PoeModule::claim_created(Origin::signed(1), vec![1], None, None));
+        let proofs = vec![vec![0], vec![1], vec![0; 11], vec![2]];
+        assert_ok!(PoeModule::claim_batch(Origin::signed(1), proofs, false));
+        assert_eq!(Proofs::<Test>::contains_key(vec![0]), true);
+        assert_eq!(Proofs::<Test>::contains_key(vec![2]), true);
+        assert_eq!(Proofs::<Test>::contains_key(vec![0; 11]), false);
+        assert_eq!(PoeModule::owned_proofs_count(1), 3);
+    })
+}
+
+/// 原子批量存证，任一失败全部回滚
+#[test]
+fn claim_batch_atomic_rolls_back(){
+    new_test_ext().execute_with(||{
+        assert_ok!(PoeModule::claim_created(Origin::signed(2), vec![1], None, None));
+        assert_noop!(
+            PoeModule::claim_batch(Origin::signed(1), vec![vec![0], vec![1], vec![2]], true),
+            Error::<Test>::ProofAlreadyClaimed
+        );
+        assert_eq!(Proofs::<Test>::contains_key(vec![0]), false);
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_ok!(PoeModule::claim_batch(Origin::signed(1), vec![vec![0], vec![2]], true));
+        assert_eq!(PoeModule::owned_proofs_count(1), 2);
+    })
+}
+
+/// 批量撤销
+#[test]
+fn revoke_batch_works(){
+    new_test_ext().execute_with(||{
+        assert_ok!(PoeModule::claim_batch(Origin::signed(1), vec![vec![0], vec![1], vec![2]], true));
+        assert_ok!(PoeModule::claim_created(Origin::signed(2), vec![3], None, None));
+        assert_noop!( // 非拥有者
+            PoeModule::revoke_batch(Origin::signed(1), vec![vec![0], vec![3]], true),
+            Error::<Test>::NotProofOwner
+        );
+        assert_ok!(PoeModule::revoke_batch(Origin::signed(1), vec![vec![0], vec![3], vec![1]], false));
+        assert_eq!(Proofs::<Test>::contains_key(vec![0]), false);
+        assert_eq!(Proofs::<Test>::contains_key(vec![1]), false);
+        assert_eq!(Proofs::<Test>::contains_key(vec![3]), true);
+        assert_eq!(PoeModule::owned_proofs_count(1), 1);
+    })
+}
+
+/// 批量数量限制
+#[test]
+fn claim_batch_failed_when_it_is_too_large(){
+    new_test_ext().execute_with(||{
+        let proofs = (0..5u8).map(|i| vec![i]).collect::<Vec<_>>();
+        assert_noop!(
+            PoeModule::claim_batch(Origin::signed(1), proofs, false),
+            Error::<Test>::BatchTooLarge
+        );
+    })
+}
+
+/// 查询存证所有者
+#[test]
+fn proof_owner_works(){
+    new_test_ext().execute_with(||{
+        let claim = vec![0, 1];
+        assert_eq!(PoeModule::proof_owner(&claim), None);
+        assert_ok!(PoeModule::claim_created(Origin::signed(1), claim.clone(), None, None));
+        assert_eq!(PoeModule::proof_owner(&claim), Some((1, 0)));
+    })
+}
+
+/// 创建多方共有存证
+#[test]
+fn claim_co_owned_works(){
+    new_test_ext().execute_with(||{
+        let claim = vec![0, 1];
+        assert_noop!(
+            PoeModule::claim_co_owned(Origin::signed(1), claim.clone(), vec![2, 3], 4),
+            Error::<Test>::InvalidThreshold
+        );
+        assert_noop!(
+            PoeModule::claim_co_owned(Origin::signed(1), claim.clone(), vec![2, 3, 4], 2),
+            Error::<Test>::TooManyCoOwners
+        );
+        assert_ok!(PoeModule::claim_co_owned(Origin::signed(1), claim.clone(), vec![2, 1, 3], 2));
+        assert_eq!(PoeModule::co_owners(&claim), Some(CoOwnership { owners: vec![1, 2, 3], threshold: 2 }));
+        assert_eq!(OwnedProofs::<Test>::contains_key(3, &claim), true);
+        assert_noop!( // 单个所有者不能直接撤销或转移
+            PoeModule::claim_revoked(Origin::signed(1), claim.clone()),
+            Error::<Test>::RequiresApproval
+        );
+        assert_noop!(
+            PoeModule::transfer_claim(Origin::signed(1), claim.clone(), 4),
+            Error::<Test>::RequiresApproval
+        );
+    })
+}
+
+/// 达到门限后撤销
+#[test]
+fn co_owned_claim_revoked_after_threshold(){
+    new_test_ext().execute_with(||{
+        let claim = vec![0, 1];
+        assert_ok!(PoeModule::claim_co_owned(Origin::signed(1), claim.clone(), vec![2, 3], 2));
+        assert_noop!(
+            PoeModule::approve_action(Origin::signed(4), claim.clone(), ClaimAction::Revoke),
+            Error::<Test>::NotProofOwner
+        );
+        assert_ok!(PoeModule::approve_action(Origin::signed(2), claim.clone(), ClaimAction::Revoke));
+        assert_noop!(
+            PoeModule::approve_action(Origin::signed(2), claim.clone(), ClaimAction::Revoke),
+            Error::<Test>::AlreadyApproved
+        );
+        assert_noop!(
+            PoeModule::approve_action(Origin::signed(3), claim.clone(), ClaimAction::Transfer(4)),
+            Error::<Test>::ConflictingAction
+        );
+        assert_eq!(Proofs::<Test>::contains_key(&claim), true);
+        assert_ok!(PoeModule::approve_action(Origin::signed(3), claim.clone(), ClaimAction::Revoke));
+        assert_eq!(Proofs::<Test>::contains_key(&claim), false);
+        assert_eq!(PoeModule::co_owners(&claim), None);
+        assert_eq!(PoeModule::pending_approval(&claim), None);
+        assert_eq!(OwnedProofs::<Test>::contains_key(2, &claim), false);
+        assert_eq!(Balances::reserved_balance(1), 0);
+    })
+}
+
+/// 达到门限后转移，新所有者单独所有
+#[test]
+fn co_owned_claim_transferred_after_threshold(){
+    new_test_ext().execute_with(||{
+        let claim = vec![0, 1];
+        assert_ok!(PoeModule::claim_co_owned(Origin::signed(1), claim.clone(), vec![2], 2));
+        assert_ok!(PoeModule::approve_action(Origin::signed(1), claim.clone(), ClaimAction::Transfer(3)));
+        assert_ok!(PoeModule::approve_action(Origin::signed(2), claim.clone(), ClaimAction::Transfer(3)));
+        assert_eq!(PoeModule::proofs(&claim).unwrap().owner, 3);
+        assert_eq!(PoeModule::co_owners(&claim), None);
+        assert_eq!(OwnedProofs::<Test>::contains_key(2, &claim), false);
+        assert_ok!(PoeModule::claim_revoked(Origin::signed(3), claim.clone()));
+    })
+}
+
+/// 批准超时作废
+#[test]
+fn co_owned_approval_expires(){
+    new_test_ext().execute_with(||{
+        let claim = vec![0, 1];
+        assert_ok!(PoeModule::claim_co_owned(Origin::signed(1), claim.clone(), vec![2, 3], 2));
+        assert_ok!(PoeModule::approve_action(Origin::signed(2), claim.clone(), ClaimAction::Revoke));
+        assert_noop!(
+            PoeModule::clear_expired_approval(Origin::signed(4), claim.clone()),
+            Error::<Test>::ApprovalNotExpired
+        );
+        run_to_block(5);
+        // 超时后重新收集，之前的批准不再计数
+        assert_ok!(PoeModule::approve_action(Origin::signed(3), claim.clone(), ClaimAction::Transfer(4)));
+        assert_eq!(PoeModule::pending_approval(&claim).map(|p| p.approvals), Some(vec![3]));
+        run_to_block(10);
+        assert_ok!(PoeModule::clear_expired_approval(Origin::signed(4), claim.clone()));
+        assert_eq!(PoeModule::pending_approval(&claim), None);
+        assert_eq!(PoeModule::proofs(&claim).unwrap().owner, 1);
+    })
+}
+
+fn new_test_ext_with_proofs(proofs: Vec<(Vec<u8>, u64)>) -> sp_io::TestExternalities {
+    let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+    GenesisConfig::<Test> { proofs }.assimilate_storage(&mut t).unwrap();
+    t.into()
+}
+
+/// 创世存证
+#[test]
+fn genesis_proofs_work(){
+    new_test_ext_with_proofs(vec![(vec![0, 1], 1), (vec![2], 2)]).execute_with(||{
+        assert_eq!(PoeModule::proof_owner(&[0, 1]), Some((1, 0)));
+        assert_eq!(PoeModule::proofs(vec![2]).map(|p| p.created_at), Some(0));
+        assert_eq!(OwnedProofs::<Test>::contains_key(2, vec![2]), true);
+        assert_noop!(
+            PoeModule::claim_created(Origin::signed(2), vec![0, 1], None, None),
+            Error::<Test>::ProofAlreadyClaimed
+        );
+    })
+}
+
+/// 创世存证长度限制
+#[test]
+#[should_panic(expected = "Genesis proof exceeds MaxProofLength")]
+fn genesis_proof_failed_when_it_is_too_long(){
+    new_test_ext_with_proofs(vec![(vec![0; 11], 1)]);
+}
+
+/// V1 -> V2 存储迁移
+#[test]
+fn migrate_to_v2_works(){
+    new_test_ext_v1(vec![
+        (vec![0, 1], 1, 5, None),
+        (vec![2], 2, 7, Some((3, 11))),
+        (vec![3], 1, 6, None),
+    ]).execute_with(||{
+        assert_eq!(StorageVersion::get(), Releases::V1);
+        PoeModule::on_runtime_upgrade();
+        assert_eq!(StorageVersion::get(), Releases::V2);
+        assert_eq!(PoeModule::proofs(vec![0, 1]), Some(ProofInfo { owner: 1, created_at: 5, updated_at: 5, deposit: 0 }));
+        assert_eq!(PoeModule::proofs(vec![2]), Some(ProofInfo { owner: 2, created_at: 3, updated_at: 7, deposit: 11 }));
+        // 已有存证补建所有者索引
+        assert!(OwnedProofs::<Test>::contains_key(1, vec![0, 1]));
+        assert!(OwnedProofs::<Test>::contains_key(1, vec![3]));
+        assert!(OwnedProofs::<Test>::contains_key(2, vec![2]));
+        assert_eq!(PoeModule::owned_proofs_count(1), 2);
+        assert_eq!(PoeModule::owned_proofs_count(2), 1);
+        // 旧的单独存储已清理
+        let hash = frame_support::Blake2_128Concat::hash(&vec![2u8].encode());
+        assert_eq!(frame_support::storage::migration::get_storage_value::<u64>(b"PoeModule", b"ProofDeposits", &hash), None);
+    })
+}
+
+/// 迁移只执行一次
+#[test]
+fn migrate_to_v2_is_idempotent(){
+    new_test_ext_v1(vec![(vec![0, 1], 1, 5, None)]).execute_with(||{
+        PoeModule::on_runtime_upgrade();
+        let migrated = PoeModule::proofs(vec![0, 1]);
+        PoeModule::on_runtime_upgrade();
+        assert_eq!(PoeModule::proofs(vec![0, 1]), migrated);
+        assert_eq!(PoeModule::owned_proofs_count(1), 1);
+    })
+}
+
+/// 新链直接使用 V2
+#[test]
+fn new_chain_starts_at_v2(){
+    new_test_ext().execute_with(||{
+        assert_eq!(StorageVersion::get(), Releases::V2);
+    })
+}
+
+/// 奇数个叶子时每个文档的包含证明都能还原树根
+#[test]
+fn merkle_tree_odd_leaf_counts(){
+    for n in [1usize, 3, 5, 7].iter() {
+        let documents: Vec<Vec<u8>> = (0..*n as u8).map(|i| vec![i]).collect();
+        let tree = merkle::MerkleTree::new(&documents).unwrap();
+        assert_eq!(tree.leaf_count(), *n as u32);
+        for (i, document) in documents.iter().enumerate() {
+            let path = tree.proof(i).unwrap();
+            assert_eq!(merkle_root_from_path(document, &path), tree.root());
+        }
+        assert_eq!(tree.proof(*n), None);
+    }
+    // 单个叶子的树根即叶子哈希
+    assert_eq!(merkle::MerkleTree::new(&[b"a"]).unwrap().root(), merkle_leaf(b"a"));
+    assert!(merkle::MerkleTree::new::<Vec<u8>>(&[]).is_none());
+}
+
+/// 奇数层的最后一个节点直接提升，不与自身配对
+#[test]
+fn merkle_tree_promotes_last_node(){
+    let tree = merkle::MerkleTree::new(&[b"a", b"b", b"c"]).unwrap();
+    let ab = merkle_parent(&merkle_leaf(b"a"), &merkle_leaf(b"b"));
+    assert_eq!(tree.root(), merkle_parent(&ab, &merkle_leaf(b"c")));
+    assert_eq!(tree.proof(2), Some(vec![MerkleStep::Left(ab)]));
+}
+
+/// 锚定 Merkle 根并校验文档
+#[test]
+fn claim_merkle_root_and_verify_inclusion_works(){
+    new_test_ext().execute_with(||{
+        let documents = vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec()];
+        let tree = merkle::MerkleTree::new(&documents).unwrap();
+        let root = tree.root();
+        assert_ok!(PoeModule::claim_merkle_root(Origin::signed(1), root, tree.leaf_count()));
+        assert_eq!(Balances::reserved_balance(1), 10 + 32);
+        assert_noop!(
+            PoeModule::claim_merkle_root(Origin::signed(2), root, 3),
+            Error::<Test>::MerkleRootAlreadyClaimed
+        );
+
+        let path = tree.proof(2).unwrap();
+        assert_ok!(PoeModule::verify_inclusion(Origin::signed(2), documents[2].clone(), path.clone(), root));
+        assert_noop!(
+            PoeModule::verify_inclusion(Origin::signed(2), b"d".to_vec(), path, root),
+            Error::<Test>::InvalidMerkleProof
+        );
+
+        assert_noop!(PoeModule::revoke_merkle_root(Origin::signed(2), root), Error::<Test>::NotProofOwner);
+        assert_ok!(PoeModule::revoke_merkle_root(Origin::signed(1), root));
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_noop!(
+            PoeModule::verify_inclusion(Origin::signed(2), documents[0].clone(), tree.proof(0).unwrap(), root),
+            Error::<Test>::NoSuchMerkleRoot
+        );
+    })
+}
+
+/// 树高按叶子数量向上取整
+#[test]
+fn merkle_depth_works(){
+    assert_eq!(merkle_depth(1), 0);
+    assert_eq!(merkle_depth(2), 1);
+    assert_eq!(merkle_depth(3), 2);
+    assert_eq!(merkle_depth(4), 2);
+    assert_eq!(merkle_depth(5), 3);
+    assert_eq!(merkle_depth(u32::max_value()), MAX_MERKLE_DEPTH);
+}
+
+/// 包含证明不能超过锚定时 leaf_count 对应的树高
+#[test]
+fn verify_inclusion_failed_when_path_exceeds_leaf_count(){
+    new_test_ext().execute_with(||{
+        let documents = vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec()];
+        let tree = merkle::MerkleTree::new(&documents).unwrap();
+        let root = tree.root();
+        assert_ok!(PoeModule::claim_merkle_root(Origin::signed(1), root, 2));
+
+        let path = tree.proof(0).unwrap();
+        assert_eq!(path.len(), 2);
+        assert_noop!(
+            PoeModule::verify_inclusion(Origin::signed(2), documents[0].clone(), path, root),
+            Error::<Test>::InvalidMerkleProof
+        );
+        assert_ok!(PoeModule::verify_inclusion(Origin::signed(2), documents[2].clone(), tree.proof(2).unwrap(), root));
+    })
+}
+
+/// 叶子数量不能为 0
+#[test]
+fn claim_merkle_root_failed_with_zero_leaves(){
+    new_test_ext().execute_with(||{
+        assert_noop!(
+            PoeModule::claim_merkle_root(Origin::signed(1), [0u8; 32], 0),
+            Error::<Test>::InvalidLeafCount
+        );
+    })
+}
+
+/// 从链下数据库读取存证的第 index 条历史
+fn offchain_history(ext: &mut sp_io::TestExternalities, proof: &[u8], index: u32) -> Option<HistoryRecord<u64, u64>> {
+    ext.persist_offchain_overlay();
+    ext.offchain_db()
+        .get(sp_core::offchain::STORAGE_PREFIX, &history_key(proof, index))
+        .map(|raw| HistoryRecord::decode(&mut &raw[..]).unwrap())
+}
+
+/// 创建、转移、撤销都会追加一条历史
+#[test]
+fn history_is_recorded(){
+    let claim = vec![0, 1];
+    let mut ext = new_test_ext();
+    ext.execute_with(||{
+        run_to_block(1);
+        assert_ok!(PoeModule::claim_created(Origin::signed(1), claim.clone(), None, None));
+        run_to_block(2);
+        assert_ok!(PoeModule::transfer_claim(Origin::signed(1), claim.clone(), 2));
+        assert_eq!(PoeModule::history_count(&claim), 2);
+        run_to_block(3);
+        assert_ok!(PoeModule::claim_revoked(Origin::signed(2), claim.clone()));
+        assert_eq!(PoeModule::history_count(&claim), 3);
+
+        // 撤销后重新创建，历史继续追加
+        assert_ok!(PoeModule::claim_created(Origin::signed(1), claim.clone(), None, None));
+        assert_eq!(PoeModule::history_count(&claim), 4);
+        // 失败的操作不记录
+        assert_noop!(PoeModule::claim_revoked(Origin::signed(2), claim.clone()), Error::<Test>::NotProofOwner);
+        assert_eq!(PoeModule::history_count(&claim), 4);
+    });
+
+    let expected = vec![
+        (HistoryAction::Created, 1, 1),
+        (HistoryAction::Transferred, 2, 2),
+        (HistoryAction::Revoked, 2, 3),
+        (HistoryAction::Created, 1, 3),
+    ];
+    for (index, (action, owner, block)) in expected.into_iter().enumerate() {
+        assert_eq!(
+            offchain_history(&mut ext, &claim, index as u32),
+            Some(HistoryRecord { action, owner, block })
+        );
+    }
+    assert_eq!(offchain_history(&mut ext, &claim, 4), None);
+}
+
+/// 过期也记录为历史
+#[test]
+fn history_records_expiry(){
+    let claim = vec![0, 1];
+    let mut ext = new_test_ext();
+    ext.execute_with(||{
+        assert_ok!(PoeModule::claim_created(Origin::signed(1), claim.clone(), None, Some(2)));
+        run_to_block(2);
+        assert_eq!(PoeModule::history_count(&claim), 2);
+    });
+
+    assert_eq!(
+        offchain_history(&mut ext, &claim, 0),
+        Some(HistoryRecord { action: HistoryAction::Created, owner: 1, block: 0 })
+    );
+    assert_eq!(
+        offchain_history(&mut ext, &claim, 1),
+        Some(HistoryRecord { action: HistoryAction::Expired, owner: 1, block: 2 })
+    );
+}
+
+/// Root 强制撤销存证
+#[test]
+fn force_revoke_works(){
+    new_test_ext().execute_with(||{
+        let claim = vec![0, 1];
+        assert_ok!(PoeModule::claim_created(Origin::signed(1), claim.clone(), None, None));
+        assert_noop!(PoeModule::force_revoke(Origin::signed(2), claim.clone(), 1), DispatchError::BadOrigin);
+        assert_ok!(PoeModule::force_revoke(Origin::root(), claim.clone(), 1));
+        assert_eq!(PoeModule::proofs(&claim), None);
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_noop!(PoeModule::force_revoke(Origin::root(), claim, 1), Error::<Test>::NoSuchProof);
+    })
+}
+
+/// 争议中的存证被冻结
+#[test]
+fn disputed_claim_is_frozen(){
+    new_test_ext().execute_with(||{
+        let claim = vec![0, 1];
+        assert_ok!(PoeModule::claim_created(Origin::signed(1), claim.clone(), None, None));
+        assert_ok!(PoeModule::dispute_claim(Origin::signed(2), claim.clone(), 7));
+        assert_eq!(Balances::reserved_balance(2), 5);
+        assert_noop!(PoeModule::dispute_claim(Origin::signed(2), claim.clone(), 7), Error::<Test>::AlreadyDisputed);
+
+        assert_noop!(PoeModule::claim_revoked(Origin::signed(1), claim.clone()), Error::<Test>::ProofDisputed);
+        assert_noop!(PoeModule::transfer_claim(Origin::signed(1), claim.clone(), 2), Error::<Test>::ProofDisputed);
+        assert_noop!(PoeModule::offer_claim(Origin::signed(1), claim.clone(), 2), Error::<Test>::ProofDisputed);
+        assert_noop!(PoeModule::set_metadata(Origin::signed(1), claim.clone(), None), Error::<Test>::ProofDisputed);
+    })
+}
+
+/// 驳回争议：存证解冻，押金赔付给所有者
+#[test]
+fn resolve_dispute_reject_works(){
+    new_test_ext().execute_with(||{
+        let claim = vec![0, 1];
+        assert_ok!(PoeModule::claim_created(Origin::signed(1), claim.clone(), None, None));
+        assert_ok!(PoeModule::dispute_claim(Origin::signed(2), claim.clone(), 7));
+        assert_noop!(PoeModule::resolve_dispute(Origin::signed(1), claim.clone(), false), DispatchError::BadOrigin);
+        assert_ok!(PoeModule::resolve_dispute(Origin::root(), claim.clone(), false));
+
+        assert_eq!(PoeModule::dispute_of(&claim), None);
+        assert_eq!(Balances::reserved_balance(2), 0);
+        assert_eq!(Balances::free_balance(2), 995);
+        assert_eq!(Balances::free_balance(1), 1000 - 12 + 5);
+        assert_ok!(PoeModule::transfer_claim(Origin::signed(1), claim, 2));
+    })
+}
+
+/// 争议中的存证到期不移除，驳回争议时随即过期
+#[test]
+fn disputed_claim_expires_after_rejection(){
+    new_test_ext().execute_with(||{
+        let claim = vec![0, 1];
+        assert_ok!(PoeModule::claim_created(Origin::signed(1), claim.clone(), None, Some(2)));
+        assert_ok!(PoeModule::dispute_claim(Origin::signed(2), claim.clone(), 7));
+        run_to_block(3);
+        assert_eq!(PoeModule::proofs(&claim).unwrap().owner, 1);
+        assert_eq!(PoeModule::expiry_of(&claim), Some(2));
+        assert_eq!(Balances::reserved_balance(2), 5);
+
+        assert_ok!(PoeModule::resolve_dispute(Origin::root(), claim.clone(), false));
+        assert_eq!(PoeModule::proofs(&claim), None);
+        assert_eq!(PoeModule::expiry_of(&claim), None);
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Balances::free_balance(1), 1000 + 5 - 2);
+        assert_eq!(Balances::free_balance(2), 995);
+    })
+}
+
+/// 争议中的存证可以延期，驳回争议后按新的过期区块处理
+#[test]
+fn disputed_claim_can_be_renewed(){
+    new_test_ext().execute_with(||{
+        let claim = vec![0, 1];
+        assert_ok!(PoeModule::claim_created(Origin::signed(1), claim.clone(), None, Some(2)));
+        assert_ok!(PoeModule::dispute_claim(Origin::signed(2), claim.clone(), 7));
+        assert_ok!(PoeModule::renew_claim(Origin::signed(1), claim.clone(), 4));
+        run_to_block(3);
+        assert_ok!(PoeModule::resolve_dispute(Origin::root(), claim.clone(), false));
+        assert_eq!(PoeModule::proofs(&claim).unwrap().owner, 1);
+        run_to_block(4);
+        assert_eq!(PoeModule::proofs(&claim), None);
+    })
+}
+
+/// 支持争议：撤销存证，退还发起方押金
+#[test]
+fn resolve_dispute_revoke_works(){
+    new_test_ext().execute_with(||{
+        let claim = vec![0, 1];
+        assert_ok!(PoeModule::claim_created(Origin::signed(1), claim.clone(), None, None));
+        assert_noop!(PoeModule::resolve_dispute(Origin::root(), claim.clone(), true), Error::<Test>::NoSuchDispute);
+        assert_ok!(PoeModule::dispute_claim(Origin::signed(2), claim.clone(), 7));
+        assert_ok!(PoeModule::resolve_dispute(Origin::root(), claim.clone(), true));
+
+        assert_eq!(PoeModule::proofs(&claim), None);
+        assert_eq!(PoeModule::dispute_of(&claim), None);
+        assert_eq!(Balances::reserved_balance(2), 0);
+        assert_eq!(Balances::free_balance(2), 1000);
+        // 撤销后可由他人重新存证
+        assert_ok!(PoeModule::claim_created(Origin::signed(2), claim, None, None));
+    })
+}
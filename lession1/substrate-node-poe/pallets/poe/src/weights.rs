@@ -0,0 +1,108 @@
+//! Weights for pallet_poe
+//!
+//! 注意：以下数值为占位估算，并非基准测试输出。存储读写次数按各调用实际访问的 key 计数，
+//! 计算部分为粗略估计。上线前请在参考硬件上运行 benchmarking.rs 中的基准测试，
+//! 用生成的文件替换本文件：
+//!
+//! ./target/release/node-template benchmark --chain=dev --steps=50 --repeat=20 \
+//!     --pallet=pallet_poe --extrinsic=* --execution=wasm --wasm-execution=compiled \
+//!     --heap-pages=4096 --output=./pallets/poe/src/weights.rs
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_poe.
+pub trait WeightInfo {
+	fn claim_created(l: u32, ) -> Weight;
+	fn claim_revoked(l: u32, ) -> Weight;
+	fn transfer_claim(l: u32, ) -> Weight;
+	fn on_initialize(e: u32, ) -> Weight;
+	fn verify_document(l: u32, ) -> Weight;
+	fn verify_inclusion(l: u32, p: u32, ) -> Weight;
+}
+
+/// pallet_poe 的占位权重，见文件头说明
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Trait> WeightInfo for SubstrateWeight<T> {
+	fn claim_created(l: u32, ) -> Weight {
+		(62_000_000 as Weight)
+			.saturating_add((14_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	fn claim_revoked(l: u32, ) -> Weight {
+		(58_000_000 as Weight)
+			.saturating_add((9_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(8 as Weight))
+	}
+	fn transfer_claim(l: u32, ) -> Weight {
+		(81_000_000 as Weight)
+			.saturating_add((11_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(7 as Weight))
+	}
+	fn on_initialize(e: u32, ) -> Weight {
+		(3_000_000 as Weight)
+			.saturating_add((71_000_000 as Weight).saturating_mul(e as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((8 as Weight).saturating_mul(e as Weight)))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes((12 as Weight).saturating_mul(e as Weight)))
+	}
+	fn verify_document(l: u32, ) -> Weight {
+		(24_000_000 as Weight)
+			.saturating_add((5_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	fn verify_inclusion(l: u32, p: u32, ) -> Weight {
+		(26_000_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add((4_000_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn claim_created(l: u32, ) -> Weight {
+		(62_000_000 as Weight)
+			.saturating_add((14_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	fn claim_revoked(l: u32, ) -> Weight {
+		(58_000_000 as Weight)
+			.saturating_add((9_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(8 as Weight))
+	}
+	fn transfer_claim(l: u32, ) -> Weight {
+		(81_000_000 as Weight)
+			.saturating_add((11_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(7 as Weight))
+	}
+	fn on_initialize(e: u32, ) -> Weight {
+		(3_000_000 as Weight)
+			.saturating_add((71_000_000 as Weight).saturating_mul(e as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((8 as Weight).saturating_mul(e as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((12 as Weight).saturating_mul(e as Weight)))
+	}
+	fn verify_document(l: u32, ) -> Weight {
+		(24_000_000 as Weight)
+			.saturating_add((5_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+	fn verify_inclusion(l: u32, p: u32, ) -> Weight {
+		(26_000_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add((4_000_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+}
@@ -247,6 +247,15 @@ impl pallet_balances::Trait for Runtime {
 parameter_types! {
 	pub const TransactionByteFee: Balance = 1;
 	pub const MaxProofLength: usize = 10;
+	pub const MaxMetadataLength: usize = 256;
+	pub const ProofDepositBase: Balance = 1_000;
+	pub const ProofDepositPerByte: Balance = 10;
+	pub const MaxExpiriesPerBlock: u32 = 50;
+	pub const MaxBatchSize: u32 = 500;
+	pub const MaxCoOwners: u32 = 16;
+	pub const ApprovalTimeout: BlockNumber = 7 * DAYS;
+	pub const DisputeDeposit: Balance = 10_000;
+	pub const HistoryDeposit: Balance = 100;
 }
 
 impl pallet_transaction_payment::Trait for Runtime {
@@ -269,6 +278,18 @@ impl pallet_template::Trait for Runtime {
 impl pallet_poe::Trait for Runtime {
 	type Event = Event;
 	type MaxProofLength = MaxProofLength;
+	type MaxMetadataLength = MaxMetadataLength;
+	type Currency = Balances;
+	type ProofDepositBase = ProofDepositBase;
+	type ProofDepositPerByte = ProofDepositPerByte;
+	type MaxExpiriesPerBlock = MaxExpiriesPerBlock;
+	type MaxBatchSize = MaxBatchSize;
+	type MaxCoOwners = MaxCoOwners;
+	type ApprovalTimeout = ApprovalTimeout;
+	type ForceOrigin = frame_system::EnsureRoot<AccountId>;
+	type DisputeDeposit = DisputeDeposit;
+	type HistoryDeposit = HistoryDeposit;
+	type WeightInfo = pallet_poe::weights::SubstrateWeight<Runtime>;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -288,7 +309,7 @@ construct_runtime!(
 		Sudo: pallet_sudo::{Module, Call, Config<T>, Storage, Event<T>},
 		// Include the custom logic from the template pallet in the runtime.
 		TemplateModule: pallet_template::{Module, Call, Storage, Event<T>},
-		PoeModule: pallet_poe::{Module, Call, Storage, Event<T>},
+		PoeModule: pallet_poe::{Module, Call, Storage, Config<T>, Event<T>},
 	}
 );
 
@@ -451,6 +472,20 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_poe_runtime_api::PoeApi<Block, AccountId, BlockNumber> for Runtime {
+		fn proof_owner(proof: Vec<u8>) -> Option<(AccountId, BlockNumber)> {
+			PoeModule::proof_owner(&proof)
+		}
+
+		fn proofs_of(account: AccountId, start: u32, count: u32) -> Vec<Vec<u8>> {
+			PoeModule::proofs_of(&account, start, count)
+		}
+
+		fn history_count(proof: Vec<u8>) -> u32 {
+			PoeModule::history_count(proof)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn dispatch_benchmark(
@@ -485,6 +520,7 @@ impl_runtime_apis! {
 			add_benchmark!(params, batches, frame_system, SystemBench::<Runtime>);
 			add_benchmark!(params, batches, pallet_balances, Balances);
 			add_benchmark!(params, batches, pallet_timestamp, Timestamp);
+			add_benchmark!(params, batches, pallet_poe, PoeModule);
 
 			if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
 			Ok(batches)
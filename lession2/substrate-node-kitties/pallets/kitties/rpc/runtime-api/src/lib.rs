@@ -0,0 +1,21 @@
+//! Runtime API definition for the kitties pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	pub trait KittiesApi<KittyIndex> where
+		KittyIndex: Codec,
+	{
+		/// 向上 depth 代内的祖先
+		fn ancestors(kitty_id: KittyIndex, depth: u32) -> Vec<KittyIndex>;
+		/// 向下 depth 代内的后代
+		fn descendants(kitty_id: KittyIndex, depth: u32) -> Vec<KittyIndex>;
+		/// 兄弟姐妹，包含同父异母或同母异父
+		fn siblings(kitty_id: KittyIndex) -> Vec<KittyIndex>;
+		/// 与其繁育过的伴侣
+		fn mates(kitty_id: KittyIndex) -> Vec<KittyIndex>;
+	}
+}
@@ -0,0 +1,108 @@
+//! RPC interface for the kitties pallet.
+
+use std::sync::Arc;
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use pallet_kitties_runtime_api::KittiesApi as KittiesRuntimeApi;
+
+#[rpc]
+pub trait KittiesApi<BlockHash, KittyIndex> {
+	/// 查询 depth 代内的祖先
+	#[rpc(name = "kitties_ancestors")]
+	fn ancestors(&self, kitty_id: KittyIndex, depth: u32, at: Option<BlockHash>) -> Result<Vec<KittyIndex>>;
+
+	/// 查询 depth 代内的后代
+	#[rpc(name = "kitties_descendants")]
+	fn descendants(&self, kitty_id: KittyIndex, depth: u32, at: Option<BlockHash>) -> Result<Vec<KittyIndex>>;
+
+	/// 查询兄弟姐妹
+	#[rpc(name = "kitties_siblings")]
+	fn siblings(&self, kitty_id: KittyIndex, at: Option<BlockHash>) -> Result<Vec<KittyIndex>>;
+
+	/// 查询伴侣
+	#[rpc(name = "kitties_mates")]
+	fn mates(&self, kitty_id: KittyIndex, at: Option<BlockHash>) -> Result<Vec<KittyIndex>>;
+}
+
+/// A struct that implements the `KittiesApi`.
+pub struct Kitties<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Kitties<C, B> {
+	/// Create new `Kitties` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+/// Error code for runtime API call failures.
+const RUNTIME_ERROR: i64 = 1;
+
+fn runtime_error(e: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(RUNTIME_ERROR),
+		message: "Unable to query kitties runtime API.".into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+impl<C, Block, KittyIndex> KittiesApi<<Block as BlockT>::Hash, KittyIndex> for Kitties<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: KittiesRuntimeApi<Block, KittyIndex>,
+	KittyIndex: Codec,
+{
+	fn ancestors(
+		&self,
+		kitty_id: KittyIndex,
+		depth: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<KittyIndex>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.ancestors(&at, kitty_id, depth).map_err(runtime_error)
+	}
+
+	fn descendants(
+		&self,
+		kitty_id: KittyIndex,
+		depth: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<KittyIndex>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.descendants(&at, kitty_id, depth).map_err(runtime_error)
+	}
+
+	fn siblings(
+		&self,
+		kitty_id: KittyIndex,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<KittyIndex>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.siblings(&at, kitty_id).map_err(runtime_error)
+	}
+
+	fn mates(
+		&self,
+		kitty_id: KittyIndex,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<KittyIndex>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.mates(&at, kitty_id).map_err(runtime_error)
+	}
+}
@@ -0,0 +1,134 @@
+//! DNA 的基因布局
+//!
+//! | 字节  | 基因          | 取值范围               |
+//! |-------|---------------|------------------------|
+//! | 0     | 身体颜色      | 0 .. BODY_COLOURS      |
+//! | 1     | 眼睛颜色      | 0 .. EYE_COLOURS       |
+//! | 2     | 眼睛形状      | EyeShape               |
+//! | 3     | 花纹          | Pattern                |
+//! | 4     | 冷却等级      | 0 ..= MAX_COOLDOWN_CLASS |
+//! | 5..7  | 代数 (大端)   | u16                    |
+//! | 7..16 | 隐性基因      | 任意                   |
+
+use codec::{Encode, Decode};
+use sp_runtime::RuntimeDebug;
+
+pub const BODY_COLOURS: u8 = 16;
+pub const EYE_COLOURS: u8 = 8;
+pub const MAX_COOLDOWN_CLASS: u8 = 7;
+const HIDDEN_GENES: usize = 9;
+
+/// 眼睛形状
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum EyeShape {
+    Round,
+    Almond,
+    Sleepy,
+    Wide,
+}
+
+impl EyeShape {
+    fn from_gene(gene: u8) -> Self {
+        match gene % 4 {
+            0 => EyeShape::Round,
+            1 => EyeShape::Almond,
+            2 => EyeShape::Sleepy,
+            _ => EyeShape::Wide,
+        }
+    }
+}
+
+/// 花纹
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum Pattern {
+    Solid,
+    Tabby,
+    Spotted,
+    Calico,
+    Tuxedo,
+}
+
+impl Pattern {
+    fn from_gene(gene: u8) -> Self {
+        match gene % 5 {
+            0 => Pattern::Solid,
+            1 => Pattern::Tabby,
+            2 => Pattern::Spotted,
+            3 => Pattern::Calico,
+            _ => Pattern::Tuxedo,
+        }
+    }
+}
+
+/// 解码后的基因
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Genome {
+    pub body_colour: u8,
+    pub eye_colour: u8,
+    pub eye_shape: EyeShape,
+    pub pattern: Pattern,
+    pub cooldown_class: u8,
+    pub generation: u16,
+    pub hidden: [u8; HIDDEN_GENES],
+}
+
+impl Genome {
+    /// 由 16 字节 DNA 解码，超出范围的字节按取值范围取模
+    pub fn from_dna(dna: &[u8; 16]) -> Self {
+        let mut hidden = [0u8; HIDDEN_GENES];
+        hidden.copy_from_slice(&dna[7..]);
+        Genome {
+            body_colour: dna[0] % BODY_COLOURS,
+            eye_colour: dna[1] % EYE_COLOURS,
+            eye_shape: EyeShape::from_gene(dna[2]),
+            pattern: Pattern::from_gene(dna[3]),
+            cooldown_class: dna[4] % (MAX_COOLDOWN_CLASS + 1),
+            generation: u16::from_be_bytes([dna[5], dna[6]]),
+            hidden,
+        }
+    }
+
+    /// 编码回 16 字节 DNA
+    pub fn to_dna(&self) -> [u8; 16] {
+        let mut dna = [0u8; 16];
+        dna[0] = self.body_colour;
+        dna[1] = self.eye_colour;
+        dna[2] = self.eye_shape as u8;
+        dna[3] = self.pattern as u8;
+        dna[4] = self.cooldown_class;
+        dna[5..7].copy_from_slice(&self.generation.to_be_bytes());
+        dna[7..].copy_from_slice(&self.hidden);
+        dna
+    }
+
+    /// 初代 kitty：由随机数决定外观，代数和冷却等级为 0
+    pub fn founder(random: &[u8; 16]) -> Self {
+        Genome { cooldown_class: 0, generation: 0, ..Self::from_dna(random) }
+    }
+
+    /// 繁育：每个显性基因按 selector 对应字节的最低位取自父或母，
+    /// 隐性基因逐位混合，代数为父母较大者加一，冷却等级随代数增长
+    pub fn breed(parent_1: &Genome, parent_2: &Genome, selector: &[u8; 16]) -> Self {
+        let pick = |i: usize| selector[i] & 1 == 1;
+        let mut hidden = [0u8; HIDDEN_GENES];
+        for i in 0..HIDDEN_GENES {
+            let s = selector[7 + i];
+            hidden[i] = (s & parent_1.hidden[i]) | (!s & parent_2.hidden[i]);
+        }
+        let generation = parent_1.generation.max(parent_2.generation).saturating_add(1);
+        Genome {
+            body_colour: if pick(0) { parent_1.body_colour } else { parent_2.body_colour },
+            eye_colour: if pick(1) { parent_1.eye_colour } else { parent_2.eye_colour },
+            eye_shape: if pick(2) { parent_1.eye_shape } else { parent_2.eye_shape },
+            pattern: if pick(3) { parent_1.pattern } else { parent_2.pattern },
+            cooldown_class: Self::cooldown_class_for(generation),
+            generation,
+            hidden,
+        }
+    }
+
+    /// 每两代冷却等级加一，最高 MAX_COOLDOWN_CLASS
+    pub fn cooldown_class_for(generation: u16) -> u8 {
+        (generation / 2).min(MAX_COOLDOWN_CLASS as u16) as u8
+    }
+}
@@ -1,51 +1,156 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use codec::{Encode, Decode};
-use frame_support::{decl_module, decl_storage, decl_event, decl_error, ensure,
-                    traits::{Randomness, Get, Currency, ExistenceRequirement::AllowDeath, ReservableCurrency},
+use frame_support::{decl_module, decl_storage, decl_event, decl_error, ensure, Parameter, weights::Weight,
+                    traits::{Randomness, Get, Currency, ExistenceRequirement::KeepAlive, ReservableCurrency, BalanceStatus},
 };
 use frame_system::{ensure_signed};
-use sp_runtime::DispatchError;
-use sp_io::hashing::blake2_128;
+use sp_runtime::{DispatchError, DispatchResult, RuntimeDebug, traits::{AtLeast32Bit, Member, Bounded, Zero}};
+use sp_std::{vec::Vec, collections::btree_set::BTreeSet};
 
+pub mod genome;
+pub mod migration;
+pub use genome::{Genome, EyeShape, Pattern};
+use sp_io::hashing::blake2_128;
 
-type KittyIndex = u32;
 
 // ID
 #[derive(Encode, Decode, Default)]
 pub struct Kitty(pub [u8; 16]); // data
 
+impl Kitty {
+    /// 解码基因
+    pub fn genome(&self) -> Genome {
+        Genome::from_dna(&self.0)
+    }
+    pub fn body_colour(&self) -> u8 {
+        self.genome().body_colour
+    }
+    pub fn eye_colour(&self) -> u8 {
+        self.genome().eye_colour
+    }
+    pub fn eye_shape(&self) -> EyeShape {
+        self.genome().eye_shape
+    }
+    pub fn pattern(&self) -> Pattern {
+        self.genome().pattern
+    }
+    pub fn cooldown_class(&self) -> u8 {
+        self.genome().cooldown_class
+    }
+    pub fn generation(&self) -> u16 {
+        self.genome().generation
+    }
+}
+
+/// 英式拍卖
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Auction<AccountId, Balance, BlockNumber> {
+    pub seller: AccountId,
+    /// 保留价，出价不得低于此价
+    pub reserve: Balance,
+    /// 在此区块 on_finalize 时结算
+    pub end_block: BlockNumber,
+    /// 当前最高出价，金额在出价人账户中质押
+    pub best_bid: Option<(AccountId, Balance)>,
+}
+
+/// 存储结构版本
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum Releases {
+    /// 没有押金记录
+    V1,
+    /// 押金记录在 KittyDeposits 中
+    V2,
+}
+
+impl Default for Releases {
+    fn default() -> Self {
+        Releases::V1
+    }
+}
+
 type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
 
 pub trait Trait: frame_system::Trait {
     type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
     type Randomness: Randomness<Self::Hash>;
-    // 2. runtime 指定 Kitty Index
-    type KittyIndexValue: Get<u32>;
+    // 2. runtime 指定 Kitty Index 类型，起始 index 由创世配置 start_index 指定
+    type KittyIndex: Parameter + Member + AtLeast32Bit + Default + Copy;
     type Currency: Currency<Self::AccountId> + ReservableCurrency<Self::AccountId>; // 6.质押
     type ReserveAmount: Get<BalanceOf<Self>>;
+    // 繁育冷却的基础区块数，实际冷却 = 基础 * (冷却等级 + 1)
+    type BreedingCooldown: Get<Self::BlockNumber>;
+    // 近亲检查向上追溯的代数，限制繁育时的存储读取量
+    type MaxInbreedingDepth: Get<u32>;
+    // 每个区块最多结束的拍卖数量，限制 on_finalize 的工作量
+    type MaxAuctionsPerBlock: Get<u32>;
+    // 拍卖最长持续的区块数，拍卖不能取消，避免kitty被长期锁定
+    type MaxAuctionDuration: Get<Self::BlockNumber>;
+    // 释放有血统记录的kitty时从押金中扣除并销毁的部分，支付永久保留的血统记录，应不大于 ReserveAmount
+    type LineageDeposit: Get<BalanceOf<Self>>;
 }
 
 decl_storage! {
 	trait Store for Module<T: Trait> as Kitties {
-		pub Kitties get(fn kitties): map hasher(blake2_128_concat) KittyIndex => Option<Kitty>;
-		pub KittiesCount get(fn kitties_count): KittyIndex;
-		pub KittyOwners get(fn kitty_owner): map hasher(blake2_128_concat) KittyIndex => Option<T::AccountId>;
+		pub Kitties get(fn kitties): map hasher(blake2_128_concat) T::KittyIndex => Option<Kitty>;
+		// 下一个 kitty index，创世时从 start_index 开始，[0, start_index) 保留不用
+		pub KittiesCount get(fn kitties_count) config(start_index): T::KittyIndex;
+		pub KittyOwners get(fn kitty_owner): map hasher(blake2_128_concat) T::KittyIndex => Option<T::AccountId>;
 		// 3. double_map记录账号所有kitty，double_map方便增删查
-		pub AccountKitties get(fn account_kitties): double_map hasher(blake2_128_concat) T::AccountId,hasher(blake2_128_concat) KittyIndex => Option<KittyIndex>;
+		pub AccountKitties get(fn account_kitties): double_map hasher(blake2_128_concat) T::AccountId,hasher(blake2_128_concat) T::KittyIndex => Option<T::KittyIndex>;
 		// 4.记录其父母
-		pub KittyParents get(fn kitty_parents): map hasher(blake2_128_concat) KittyIndex => (KittyIndex, KittyIndex);
+		pub KittyParents get(fn kitty_parents): map hasher(blake2_128_concat) T::KittyIndex => (T::KittyIndex, T::KittyIndex);
 		// 4.记录所有孩子
-		pub KittyChidren get(fn kitty_chidren): double_map hasher(blake2_128_concat) KittyIndex,hasher(blake2_128_concat) KittyIndex => Option<KittyIndex>;
+		pub KittyChidren get(fn kitty_chidren): double_map hasher(blake2_128_concat) T::KittyIndex,hasher(blake2_128_concat) T::KittyIndex => Option<T::KittyIndex>;
 		// 4.伴侣
-		pub KittyMate get(fn kitty_mate): map hasher(blake2_128_concat) (KittyIndex, KittyIndex) => Option<KittyIndex>;
+		pub KittyMate get(fn kitty_mate): map hasher(blake2_128_concat) (T::KittyIndex, T::KittyIndex) => Option<T::KittyIndex>;
+		// 押金记录 (质押账号, 金额)，转移时随所有权转移
+		pub KittyDeposits get(fn kitty_deposit): map hasher(blake2_128_concat) T::KittyIndex => Option<(T::AccountId, BalanceOf<T>)>;
+		// 下次可繁育的区块，代数与冷却等级记录在基因中
+		pub NextBreedAt get(fn next_breed_at): map hasher(blake2_128_concat) T::KittyIndex => T::BlockNumber;
+		// 出租配种的kitty及配种费，转移后自动清除
+		pub SireOffers get(fn sire_fee): map hasher(blake2_128_concat) T::KittyIndex => Option<BalanceOf<T>>;
+		// 挂单价格，转移后自动清除
+		pub KittyPrices get(fn kitty_price): map hasher(blake2_128_concat) T::KittyIndex => Option<BalanceOf<T>>;
+		// 拍卖中的kitty，拍卖期间不能转移、繁育、挂单
+		pub Auctions get(fn auction_of): map hasher(blake2_128_concat) T::KittyIndex => Option<Auction<T::AccountId, BalanceOf<T>, T::BlockNumber>>;
+		// 每个区块结束的拍卖
+		pub AuctionsEnding get(fn auctions_ending): map hasher(twox_64_concat) T::BlockNumber => Vec<T::KittyIndex>;
+		// 存储结构版本，新链直接为 V2
+		StorageVersion build(|_| Releases::V2): Releases;
 	}
 }
 
 decl_event!(
-	pub enum Event<T> where AccountId = <T as frame_system::Trait>::AccountId {
+	pub enum Event<T> where
+		AccountId = <T as frame_system::Trait>::AccountId,
+		Balance = BalanceOf<T>,
+		BlockNumber = <T as frame_system::Trait>::BlockNumber,
+		KittyIndex = <T as Trait>::KittyIndex,
+	{
 		Created(AccountId, KittyIndex),
 		Transferred(AccountId, AccountId, KittyIndex),
+		/// 挂单或撤单，None 为撤单 [owner, kitty_id, price]
+		Ask(AccountId, KittyIndex, Option<Balance>),
+		/// 成交 [seller, buyer, kitty_id, price]
+		Sold(AccountId, AccountId, KittyIndex, Balance),
+		/// 发起拍卖 [seller, kitty_id, reserve, end_block]
+		AuctionCreated(AccountId, KittyIndex, Balance, BlockNumber),
+		/// 出价 [bidder, kitty_id, amount]
+		Bid(AccountId, KittyIndex, Balance),
+		/// 拍卖成交 [seller, winner, kitty_id, amount]
+		AuctionSettled(AccountId, AccountId, KittyIndex, Balance),
+		/// 流拍 [seller, kitty_id]
+		AuctionUnsold(AccountId, KittyIndex),
+		/// 出租配种 [owner, sire_id, fee]
+		SireOffered(AccountId, KittyIndex, Balance),
+		/// 取消出租 [owner, sire_id]
+		SireOfferCancelled(AccountId, KittyIndex),
+		/// 租用配种 [breeder, sire_owner, sire_id, fee]
+		SireHired(AccountId, AccountId, KittyIndex, Balance),
+		/// 释放kitty，押金退还 [owner, kitty_id]
+		Released(AccountId, KittyIndex),
 	}
 );
 
@@ -55,6 +160,22 @@ decl_error! {
 		InvalidKittyId,
 		RrquireDifferentParent,
 		NotKittyOwner,
+		NotForSale,
+		PriceTooLow,
+		BuyerIsOwner,
+		KittyLocked,
+		BreedingOnCooldown,
+		SireNotOffered,
+		InsufficientBalance,
+		InbreedingNotAllowed,
+		InvalidEndBlock,
+		AuctionNotFound,
+		AuctionEnded,
+		BidTooLow,
+		TooManyAuctions,
+		AuctionTooLong,
+		SireFeeTooHigh,
+		RecipientNotFound,
 	}
 }
 
@@ -64,94 +185,399 @@ decl_module! {
 
 		fn deposit_event() = default;
 
+		/// 为旧kitty补记押金，见 migration::migrate_to_v2
+		fn on_runtime_upgrade() -> Weight {
+			migration::migrate_to_v2::<T>()
+		}
+
+		/// 预先计入 on_finalize 结算拍卖的权重，数量受 MaxAuctionsPerBlock 限制
+		fn on_initialize(n: T::BlockNumber) -> Weight {
+			let count = AuctionsEnding::<T>::decode_len(n).unwrap_or(0) as Weight;
+			// 每场拍卖：Auctions、押金记录、所有权、挂单、配种出租及买卖双方余额
+			T::DbWeight::get().reads_writes(1 + 6 * count, 1 + 11 * count)
+		}
+
+		/// 结算本区块结束的拍卖
+		fn on_finalize(n: T::BlockNumber) {
+			for kitty_id in AuctionsEnding::<T>::take(n) {
+				Self::settle_auction(kitty_id);
+			}
+		}
+
 		#[weight = 0]
 		pub fn create(origin) { // 创建kitty
 			let sender = ensure_signed(origin)?;
 			let kitty_id = Self::next_kitty_id()?; // 取id
-			let dna = Self::random_value(&sender);
+			let dna = Genome::founder(&Self::random_value(&sender)).to_dna();
             let kitty = Kitty(dna);
-            Self::insert_kitty(&sender, kitty_id, kitty);
             //质押
-            T::Currency::reserve(&sender, T::ReserveAmount::get()).map_err(|_| "locker can't afford to lock the amount requested")?;
+            Self::reserve_deposit(&sender, kitty_id)?;
+            Self::insert_kitty(&sender, kitty_id, kitty);
 			Self::deposit_event(RawEvent::Created(sender, kitty_id));
 		}
 
 		#[weight = 0]
-		pub fn transfer(origin, to: T::AccountId, kitty_id: KittyIndex){
+		pub fn transfer(origin, to: T::AccountId, kitty_id: T::KittyIndex){
             let sender = ensure_signed(origin)?;
             let account_id = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?; // 1.bug，没有验证所有者
             ensure!(account_id == sender.clone(), Error::<T>::NotKittyOwner);
             let _kit = Self::kitties(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
-            Self::remove_account_kitty(&sender, kitty_id);
-            Self::insert_account_kitty(&to, kitty_id);
-
-            <KittyOwners<T>>::insert(kitty_id, to.clone());
-            T::Currency::transfer(&sender, &to, T::ReserveAmount::get(), AllowDeath)?;
+            Self::ensure_not_locked(kitty_id)?;
+            Self::do_transfer(&sender, &to, kitty_id)?;
             Self::deposit_event(RawEvent::Transferred(sender, to, kitty_id));
 		}
-        /// 孕育kitty
+
+		/// 挂单出售，price 为 None 时撤单
+		#[weight = 0]
+		pub fn set_price(origin, kitty_id: T::KittyIndex, price: Option<BalanceOf<T>>){
+            let sender = ensure_signed(origin)?;
+            let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+            ensure!(owner == sender, Error::<T>::NotKittyOwner);
+            Self::ensure_not_locked(kitty_id)?;
+
+            <KittyPrices<T>>::mutate_exists(kitty_id, |p| *p = price);
+            Self::deposit_event(RawEvent::Ask(sender, kitty_id, price));
+		}
+
+		/// 按挂单价购买，max_price 防止卖方在交易打包前抬价
+		#[weight = 0]
+		pub fn buy(origin, kitty_id: T::KittyIndex, max_price: BalanceOf<T>){
+            let sender = ensure_signed(origin)?;
+            let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+            ensure!(owner != sender, Error::<T>::BuyerIsOwner);
+            let price = Self::kitty_price(kitty_id).ok_or(Error::<T>::NotForSale)?;
+            ensure!(price <= max_price, Error::<T>::PriceTooLow);
+
+            T::Currency::transfer(&sender, &owner, price, KeepAlive)?;
+            Self::do_transfer(&owner, &sender, kitty_id)?;
+            Self::deposit_event(RawEvent::Sold(owner, sender, kitty_id, price));
+		}
+
+		/// 发起拍卖，end_block 结束时自动结算，最长 MaxAuctionDuration 个区块
+		#[weight = 0]
+		pub fn create_auction(origin, kitty_id: T::KittyIndex, reserve: BalanceOf<T>, end_block: T::BlockNumber){
+            let sender = ensure_signed(origin)?;
+            let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+            ensure!(owner == sender, Error::<T>::NotKittyOwner);
+            Self::ensure_not_locked(kitty_id)?;
+            let now = <frame_system::Module<T>>::block_number();
+            ensure!(end_block > now, Error::<T>::InvalidEndBlock);
+            ensure!(end_block <= now.saturating_add(T::MaxAuctionDuration::get()), Error::<T>::AuctionTooLong);
+            ensure!(
+                (<AuctionsEnding<T>>::decode_len(end_block).unwrap_or(0) as u32) < T::MaxAuctionsPerBlock::get(),
+                Error::<T>::TooManyAuctions
+            );
+
+            <KittyPrices<T>>::remove(kitty_id); // 拍卖期间不能按挂单价购买
+            <SireOffers<T>>::remove(kitty_id);
+            <Auctions<T>>::insert(kitty_id, Auction { seller: sender.clone(), reserve, end_block, best_bid: None });
+            <AuctionsEnding<T>>::append(end_block, kitty_id);
+            Self::deposit_event(RawEvent::AuctionCreated(sender, kitty_id, reserve, end_block));
+		}
+
+		/// 出价，金额在出价人账户中质押，被超过时退还
 		#[weight = 0]
-		pub fn breed(origin, kitty_id_1: KittyIndex, kitty_id_2: KittyIndex){
+		pub fn bid(origin, kitty_id: T::KittyIndex, amount: BalanceOf<T>){
             let sender = ensure_signed(origin)?;
+            let mut auction = Self::auction_of(kitty_id).ok_or(Error::<T>::AuctionNotFound)?;
+            ensure!(<frame_system::Module<T>>::block_number() < auction.end_block, Error::<T>::AuctionEnded);
+            ensure!(auction.seller != sender, Error::<T>::BuyerIsOwner);
+            ensure!(amount >= auction.reserve, Error::<T>::BidTooLow);
+            if let Some((_, best)) = &auction.best_bid {
+                ensure!(amount > *best, Error::<T>::BidTooLow);
+            }
+
+            T::Currency::reserve(&sender, amount)?;
+            if let Some((outbid, best)) = auction.best_bid.take() {
+                T::Currency::unreserve(&outbid, best);
+            }
+            auction.best_bid = Some((sender.clone(), amount));
+            <Auctions<T>>::insert(kitty_id, auction);
+            Self::deposit_event(RawEvent::Bid(sender, kitty_id, amount));
+		}
+        /// 孕育kitty
+		#[weight = T::DbWeight::get().reads_writes(Module::<T>::inbreeding_check_reads().saturating_add(13), 12)]
+		pub fn breed(origin, kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex){
+            let sender = ensure_signed(origin)?;
+            // 只能用自己的kitty繁育，他人的kitty需通过 breed_with_sire 租用
+            ensure!(Self::kitty_owner(kitty_id_1).ok_or(Error::<T>::InvalidKittyId)? == sender, Error::<T>::NotKittyOwner);
+            ensure!(Self::kitty_owner(kitty_id_2).ok_or(Error::<T>::InvalidKittyId)? == sender, Error::<T>::NotKittyOwner);
             let new_kitty_id = Self::do_breed(&sender, kitty_id_1, kitty_id_2)?;
-            //6. 质押
-            T::Currency::reserve(&sender, T::ReserveAmount::get()).map_err(|_| "locker can't afford to lock the amount requested")?;
 			Self::deposit_event(RawEvent::Created(sender, new_kitty_id));
 		}
 
+		/// 释放kitty并退还押金，有血统记录时扣除 LineageDeposit
+		#[weight = 0]
+		pub fn release(origin, kitty_id: T::KittyIndex){
+            let sender = ensure_signed(origin)?;
+            let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+            ensure!(owner == sender, Error::<T>::NotKittyOwner);
+            Self::ensure_not_locked(kitty_id)?;
+
+            Self::remove_kitty(&sender, kitty_id);
+            Self::deposit_event(RawEvent::Released(sender, kitty_id));
+		}
+
+		/// 出租kitty配种，他人支付 fee 后可与其繁育
+		#[weight = 0]
+		pub fn offer_sire(origin, kitty_id: T::KittyIndex, fee: BalanceOf<T>){
+            let sender = ensure_signed(origin)?;
+            let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+            ensure!(owner == sender, Error::<T>::NotKittyOwner);
+            Self::ensure_not_locked(kitty_id)?;
+
+            <SireOffers<T>>::insert(kitty_id, fee);
+            Self::deposit_event(RawEvent::SireOffered(sender, kitty_id, fee));
+		}
+
+		/// 取消出租
+		#[weight = 0]
+		pub fn cancel_sire_offer(origin, kitty_id: T::KittyIndex){
+            let sender = ensure_signed(origin)?;
+            let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+            ensure!(owner == sender, Error::<T>::NotKittyOwner);
+            ensure!(<SireOffers<T>>::contains_key(kitty_id), Error::<T>::SireNotOffered);
+
+            <SireOffers<T>>::remove(kitty_id);
+            Self::deposit_event(RawEvent::SireOfferCancelled(sender, kitty_id));
+		}
+
+		/// 用自己的kitty与出租的kitty繁育，支付配种费，孩子归调用者。max_fee 防止出租方在交易打包前抬价
+		// ensure_can_breed 在付费前和 do_breed 中各执行一次
+		#[weight = T::DbWeight::get().reads_writes(Module::<T>::inbreeding_check_reads().saturating_mul(2).saturating_add(15), 13)]
+		pub fn breed_with_sire(origin, my_kitty: T::KittyIndex, sire_id: T::KittyIndex, max_fee: BalanceOf<T>){
+            let sender = ensure_signed(origin)?;
+            ensure!(Self::kitty_owner(my_kitty).ok_or(Error::<T>::InvalidKittyId)? == sender, Error::<T>::NotKittyOwner);
+            let sire_owner = Self::kitty_owner(sire_id).ok_or(Error::<T>::InvalidKittyId)?;
+            let fee = Self::sire_fee(sire_id).ok_or(Error::<T>::SireNotOffered)?;
+            ensure!(fee <= max_fee, Error::<T>::SireFeeTooHigh);
+            // 先校验能否繁育、id 是否用尽、能否支付配种费和押金，再付费，付费后的 do_breed 不会失败
+            Self::ensure_can_breed(my_kitty, sire_id)?;
+            Self::next_kitty_id()?;
+            ensure!(
+                T::Currency::can_reserve(&sender, fee.saturating_add(T::ReserveAmount::get())),
+                Error::<T>::InsufficientBalance
+            );
+
+            T::Currency::transfer(&sender, &sire_owner, fee, KeepAlive)?;
+            let new_kitty_id = Self::do_breed(&sender, my_kitty, sire_id)?;
+            Self::deposit_event(RawEvent::SireHired(sender.clone(), sire_owner, sire_id, fee));
+			Self::deposit_event(RawEvent::Created(sender, new_kitty_id));
+		}
 	}
 }
-fn combine_dna(dna1: u8, dna2: u8, selector: u8) -> u8 {
-    (selector & dna1) | (!selector & dna2)
-}
 
 impl<T: Trait> Module<T> {
+    /// 向上 depth 代内的祖先，按代排列；血统记录在释放后保留，已释放的祖先照常追溯
+    pub fn ancestors(kitty_id: T::KittyIndex, depth: u32) -> Vec<T::KittyIndex> {
+        let mut result = Vec::new();
+        let mut seen = BTreeSet::new();
+        let mut current = sp_std::vec![kitty_id];
+        for _ in 0..depth {
+            let mut next = Vec::new();
+            for id in current {
+                if !<KittyParents<T>>::contains_key(id) {
+                    continue;
+                }
+                let (parent_1, parent_2) = <KittyParents<T>>::get(id);
+                for parent in [parent_1, parent_2].iter() {
+                    if seen.insert(*parent) {
+                        result.push(*parent);
+                        next.push(*parent);
+                    }
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            current = next;
+        }
+        result
+    }
+    /// 向下 depth 代内的后代，按代排列
+    pub fn descendants(kitty_id: T::KittyIndex, depth: u32) -> Vec<T::KittyIndex> {
+        let mut result = Vec::new();
+        let mut current = sp_std::vec![kitty_id];
+        for _ in 0..depth {
+            let mut next = Vec::new();
+            for id in current {
+                for child in <KittyChidren<T>>::iter_prefix_values(id) {
+                    if !result.contains(&child) {
+                        result.push(child);
+                        next.push(child);
+                    }
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            current = next;
+        }
+        result
+    }
+    /// 兄弟姐妹，包含同父异母或同母异父
+    pub fn siblings(kitty_id: T::KittyIndex) -> Vec<T::KittyIndex> {
+        Self::ancestors(kitty_id, 1)
+            .into_iter()
+            .fold(Vec::new(), |mut result, parent| {
+                for child in <KittyChidren<T>>::iter_prefix_values(parent) {
+                    if child != kitty_id && !result.contains(&child) {
+                        result.push(child);
+                    }
+                }
+                result
+            })
+    }
+    /// 与其繁育过的伴侣，包含已释放的
+    pub fn mates(kitty_id: T::KittyIndex) -> Vec<T::KittyIndex> {
+        let mut result = Vec::new();
+        for child in <KittyChidren<T>>::iter_prefix_values(kitty_id) {
+            let (parent_1, parent_2) = <KittyParents<T>>::get(child);
+            let mate = if parent_1 == kitty_id { parent_2 } else { parent_1 };
+            if !result.contains(&mate) {
+                result.push(mate);
+            }
+        }
+        result
+    }
     /// 孕育
-    fn do_breed(sender: &T::AccountId, kitty_id_1: KittyIndex, kitty_id_2: KittyIndex) -> sp_std::result::Result<KittyIndex, DispatchError> {
-        // 查询两个Kitty存在
-        let kitty1 = Self::kitties(kitty_id_1).ok_or(Error::<T>::InvalidKittyId)?;
-        let kitty2 = Self::kitties(kitty_id_2).ok_or(Error::<T>::InvalidKittyId)?;
-        // 验证非同一kitty
-        ensure!(kitty_id_1 != kitty_id_2, Error::<T>::RrquireDifferentParent);
+    fn do_breed(sender: &T::AccountId, kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex) -> sp_std::result::Result<T::KittyIndex, DispatchError> {
+        let (kitty1, kitty2) = Self::ensure_can_breed(kitty_id_1, kitty_id_2)?;
         // 下个id
         let kitty_id = Self::next_kitty_id()?;
-        let kitty_1_dna = kitty1.0;
-        let kitty_2_dna = kitty2.0;
-        // dna
-        let selector = Self::random_value(&sender); // dna
-        let mut new_dna = [0u8; 16];
-        for i in 0..kitty_1_dna.len() {
-            new_dna[i] = combine_dna(kitty_1_dna[i], kitty_2_dna[i], selector[i]);
-        }
+        //6. 质押
+        Self::reserve_deposit(sender, kitty_id)?;
+        // 逐个基因遗传
+        let selector = Self::random_value(&sender);
+        let new_dna = Genome::breed(&kitty1.genome(), &kitty2.genome(), &selector).to_dna();
         Self::insert_kitty(sender, kitty_id, Kitty(new_dna)); // 插入
         // 记录其父母
-        KittyParents::insert(kitty_id, (kitty_id_1, kitty_id_2));
+        <KittyParents<T>>::insert(kitty_id, (kitty_id_1, kitty_id_2));
         // 记录孩子
-        KittyChidren::insert(kitty_id_1, kitty_id, kitty_id);
-        KittyChidren::insert(kitty_id_2, kitty_id, kitty_id);
+        <KittyChidren<T>>::insert(kitty_id_1, kitty_id, kitty_id);
+        <KittyChidren<T>>::insert(kitty_id_2, kitty_id, kitty_id);
         // 互为伴侣
-        KittyMate::insert((kitty_id_1, kitty_id_2), kitty_id_1);
+        <KittyMate<T>>::insert((kitty_id_1, kitty_id_2), kitty_id_1);
+        // 父母进入冷却
+        let now = <frame_system::Module<T>>::block_number();
+        <NextBreedAt<T>>::insert(kitty_id_1, now.saturating_add(Self::breeding_cooldown(&kitty1)));
+        <NextBreedAt<T>>::insert(kitty_id_2, now.saturating_add(Self::breeding_cooldown(&kitty2)));
         Ok(kitty_id) // 返回
     }
+    /// 校验两个kitty能否繁育，返回父母
+    fn ensure_can_breed(kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex) -> sp_std::result::Result<(Kitty, Kitty), DispatchError> {
+        // 查询两个Kitty存在
+        let kitty1 = Self::kitties(kitty_id_1).ok_or(Error::<T>::InvalidKittyId)?;
+        let kitty2 = Self::kitties(kitty_id_2).ok_or(Error::<T>::InvalidKittyId)?;
+        // 验证非同一kitty
+        ensure!(kitty_id_1 != kitty_id_2, Error::<T>::RrquireDifferentParent);
+        Self::ensure_not_locked(kitty_id_1)?;
+        Self::ensure_not_locked(kitty_id_2)?;
+        let now = <frame_system::Module<T>>::block_number();
+        ensure!(
+            now >= Self::next_breed_at(kitty_id_1) && now >= Self::next_breed_at(kitty_id_2),
+            Error::<T>::BreedingOnCooldown
+        );
+        Self::ensure_not_inbreeding(kitty_id_1, kitty_id_2)?;
+        Ok((kitty1, kitty2))
+    }
+    /// 近亲检查最多读取 KittyParents 的次数：双方各向上追溯 MaxInbreedingDepth 代，
+    /// 每方最多查询 2^MaxInbreedingDepth 个kitty，每个读取两次
+    fn inbreeding_check_reads() -> Weight {
+        2u64.saturating_pow(T::MaxInbreedingDepth::get().saturating_add(1)).saturating_mul(2)
+    }
+    /// 禁止直系祖先/后代之间、以及同父同母的兄弟姐妹之间繁育
+    fn ensure_not_inbreeding(kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex) -> DispatchResult {
+        let depth = T::MaxInbreedingDepth::get();
+        ensure!(!Self::ancestors(kitty_id_1, depth).contains(&kitty_id_2), Error::<T>::InbreedingNotAllowed);
+        ensure!(!Self::ancestors(kitty_id_2, depth).contains(&kitty_id_1), Error::<T>::InbreedingNotAllowed);
+        if <KittyParents<T>>::contains_key(kitty_id_1) && <KittyParents<T>>::contains_key(kitty_id_2) {
+            let (a1, a2) = <KittyParents<T>>::get(kitty_id_1);
+            let (b1, b2) = <KittyParents<T>>::get(kitty_id_2);
+            ensure!(!((a1 == b1 && a2 == b2) || (a1 == b2 && a2 == b1)), Error::<T>::InbreedingNotAllowed);
+        }
+        Ok(())
+    }
+    /// 转移 kitty，同时清除挂单和配种出租。押金只能转入已存在的账户，接收方须有余额
+    fn do_transfer(from: &T::AccountId, to: &T::AccountId, kitty_id: T::KittyIndex) -> DispatchResult {
+        ensure!(!T::Currency::total_balance(to).is_zero(), Error::<T>::RecipientNotFound);
+        // 押金从原质押账号的保留余额转到新所有者的保留余额
+        if let Some((holder, amount)) = Self::kitty_deposit(kitty_id) {
+            T::Currency::repatriate_reserved(&holder, to, amount, BalanceStatus::Reserved)?;
+            <KittyDeposits<T>>::insert(kitty_id, (to.clone(), amount));
+        }
+        Self::remove_account_kitty(from, kitty_id);
+        Self::insert_account_kitty(to, kitty_id);
+        <KittyOwners<T>>::insert(kitty_id, to);
+        <KittyPrices<T>>::remove(kitty_id);
+        <SireOffers<T>>::remove(kitty_id);
+        Ok(())
+    }
+    /// 删除kitty并退还押金。kitty id 不会复用，KittyParents、KittyChidren、KittyMate 作为血统记录保留，
+    /// 否则释放中间一代后祖孙之间的近亲检查会失效；保留的记录由押金中的 LineageDeposit 支付
+    fn remove_kitty(owner: &T::AccountId, kitty_id: T::KittyIndex) {
+        let has_lineage = <KittyParents<T>>::contains_key(kitty_id)
+            || <KittyChidren<T>>::iter_prefix_values(kitty_id).next().is_some();
+        <Kitties<T>>::remove(kitty_id);
+        <KittyOwners<T>>::remove(kitty_id);
+        Self::remove_account_kitty(owner, kitty_id);
+        <KittyPrices<T>>::remove(kitty_id);
+        <SireOffers<T>>::remove(kitty_id);
+        <NextBreedAt<T>>::remove(kitty_id);
+        if let Some((holder, amount)) = <KittyDeposits<T>>::take(kitty_id) {
+            let lineage_fee = if has_lineage { T::LineageDeposit::get().min(amount) } else { Zero::zero() };
+            let _ = T::Currency::slash_reserved(&holder, lineage_fee);
+            T::Currency::unreserve(&holder, amount - lineage_fee);
+        }
+    }
+    /// 质押押金并记录质押账号
+    fn reserve_deposit(who: &T::AccountId, kitty_id: T::KittyIndex) -> DispatchResult {
+        let amount = T::ReserveAmount::get();
+        T::Currency::reserve(who, amount).map_err(|_| "locker can't afford to lock the amount requested")?;
+        <KittyDeposits<T>>::insert(kitty_id, (who.clone(), amount));
+        Ok(())
+    }
+    /// 冷却随冷却等级增长
+    fn breeding_cooldown(kitty: &Kitty) -> T::BlockNumber {
+        T::BreedingCooldown::get().saturating_mul((kitty.cooldown_class() as u32 + 1).into())
+    }
+    /// 拍卖中的kitty被锁定
+    fn ensure_not_locked(kitty_id: T::KittyIndex) -> DispatchResult {
+        ensure!(!<Auctions<T>>::contains_key(kitty_id), Error::<T>::KittyLocked);
+        Ok(())
+    }
+    /// 结算拍卖：最高出价转给卖方，kitty转给买方；无人出价或转移失败则流拍并退还出价
+    fn settle_auction(kitty_id: T::KittyIndex) {
+        let auction = match <Auctions<T>>::take(kitty_id) {
+            Some(auction) => auction,
+            None => return,
+        };
+        if let Some((winner, amount)) = auction.best_bid {
+            if Self::do_transfer(&auction.seller, &winner, kitty_id).is_ok() {
+                let _ = T::Currency::repatriate_reserved(&winner, &auction.seller, amount, BalanceStatus::Free);
+                Self::deposit_event(RawEvent::AuctionSettled(auction.seller, winner, kitty_id, amount));
+                return;
+            }
+            T::Currency::unreserve(&winner, amount);
+        }
+        Self::deposit_event(RawEvent::AuctionUnsold(auction.seller, kitty_id));
+    }
     // 插入
-    fn insert_kitty(owner: &T::AccountId, kitty_id: KittyIndex, kitty: Kitty) {
+    fn insert_kitty(owner: &T::AccountId, kitty_id: T::KittyIndex, kitty: Kitty) {
         Self::insert_account_kitty(owner, kitty_id);
-        Kitties::insert(kitty_id, kitty); // 插入kitty
-        KittiesCount::put(kitty_id + 1); // 下一个index
+        <Kitties<T>>::insert(kitty_id, kitty); // 插入kitty
+        <KittiesCount<T>>::put(kitty_id + 1.into()); // 下一个index
         <KittyOwners<T>>::insert(kitty_id, owner); // kitty所有者
     }
-    fn insert_account_kitty(owner: &T::AccountId, kitty_id: KittyIndex) {
+    fn insert_account_kitty(owner: &T::AccountId, kitty_id: T::KittyIndex) {
         <AccountKitties<T>>::insert(owner, kitty_id, kitty_id);
     }
-    fn remove_account_kitty(owner: &T::AccountId, kitty_id: KittyIndex) {
+    fn remove_account_kitty(owner: &T::AccountId, kitty_id: T::KittyIndex) {
         <AccountKitties<T>>::remove(owner, kitty_id);
     }
-    fn next_kitty_id() -> sp_std::result::Result<KittyIndex, DispatchError> {
-        let mut kitty_id = Self::kitties_count(); // 获取
-        if kitty_id == 0 {
-            kitty_id = T::KittyIndexValue::get();
-        }
-        if kitty_id == KittyIndex::max_value() {
+    fn next_kitty_id() -> sp_std::result::Result<T::KittyIndex, DispatchError> {
+        let kitty_id = Self::kitties_count(); // 获取
+        if kitty_id == T::KittyIndex::max_value() {
             return Err(Error::<T>::KittiesCountOverFlow.into());
         }
         Ok(kitty_id)
@@ -172,7 +598,7 @@ mod tests {
     use super::*;
     use sp_core::H256;
     use frame_support::{impl_outer_event, impl_outer_origin, parameter_types, weights::Weight, assert_ok, assert_noop,
-                        traits::{OnFinalize, OnInitialize},
+                        traits::{OnFinalize, OnInitialize, OnRuntimeUpgrade},
     };
     use sp_runtime::{
         traits::{BlakeTwo256, IdentityLookup}, testing::Header, Perbill,
@@ -191,8 +617,12 @@ mod tests {
         pub const MaximumBlockLength: u32 = 2 * 1024;
         pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
         pub const ExistentialDeposit: u64 = 1;
-        pub const KittyIndexValue: u32 = 0;
 	pub const ReserveAmount: u64 = 10;
+        pub const BreedingCooldown: u64 = 5;
+        pub const MaxInbreedingDepth: u32 = 3;
+        pub const MaxAuctionsPerBlock: u32 = 2;
+        pub const MaxAuctionDuration: u64 = 100;
+        pub const LineageDeposit: u64 = 2;
     }
 
     impl system::Trait for Test {
@@ -249,9 +679,14 @@ mod tests {
     impl Trait for Test {
         type Event = TestEvent;
         type Randomness = Randomness;
-        type KittyIndexValue = KittyIndexValue;
+        type KittyIndex = u32;
         type Currency = pallet_balances::Module<Self>;
         type ReserveAmount = ReserveAmount;
+        type BreedingCooldown = BreedingCooldown;
+        type MaxInbreedingDepth = MaxInbreedingDepth;
+        type MaxAuctionsPerBlock = MaxAuctionsPerBlock;
+        type MaxAuctionDuration = MaxAuctionDuration;
+        type LineageDeposit = LineageDeposit;
     }
 
     pub type Kitties = Module<Test>;
@@ -268,11 +703,17 @@ mod tests {
     }
 
     pub fn new_test_ext() -> sp_io::TestExternalities {
+        new_test_ext_with_start(0)
+    }
+
+    /// 指定创世 start_index
+    pub fn new_test_ext_with_start(start_index: u32) -> sp_io::TestExternalities {
         let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
         pallet_balances::GenesisConfig::<Test> {
             balances: vec![(1, 10000), (2, 11000), (3, 12000), (4, 13000), (5, 14000)],
         }.assimilate_storage(&mut t)
             .unwrap();
+        GenesisConfig::<Test> { start_index }.assimilate_storage(&mut t).unwrap();
         let mut ext: sp_io::TestExternalities = t.into();
         ext.execute_with(|| System::set_block_number(1));
         ext
@@ -410,14 +851,617 @@ mod tests {
             assert_ok!(Kitties::create(Origin::signed(1))); //0
             assert_ok!(Kitties::create(Origin::signed(1))); //1
             assert_ok!(Kitties::breed(Origin::signed(1), 0 , 1)); //2
+            run_to_block(15); // 等待冷却
             assert_ok!(Kitties::breed(Origin::signed(1), 0 , 1)); //3
             assert_ok!(Kitties::create(Origin::signed(1))); //4
             assert_ok!(Kitties::breed(Origin::signed(1), 2 , 4)); //5
-            assert_eq!(KittyParents::get(2), (0, 1)); // 验证其父母
-            assert_eq!(KittyChidren::iter_prefix_values(0).count(), 2); // 两个孩子
-            assert_eq!(KittyMate::get((0, 1)), Some(0)); // 0，1互为伴侣
-            assert_eq!(KittyMate::get((2, 4)), Some(2)); // 2，4互为伴侣
+            assert_eq!(KittyParents::<Test>::get(2), (0, 1)); // 验证其父母
+            assert_eq!(KittyChidren::<Test>::iter_prefix_values(0).count(), 2); // 两个孩子
+            assert_eq!(KittyMate::<Test>::get((0, 1)), Some(0)); // 0，1互为伴侣
+            assert_eq!(KittyMate::<Test>::get((2, 4)), Some(2)); // 2，4互为伴侣
+        })
+    }
+
+    /// 挂单后购买
+    #[test]
+    fn buy_kitty_works() {
+        new_test_ext().execute_with(|| {
+            run_to_block(10);
+            assert_ok!(Kitties::create(Origin::signed(1)));
+            assert_ok!(Kitties::set_price(Origin::signed(1), 0, Some(100)));
+            assert_eq!(Kitties::kitty_price(0), Some(100));
+            assert_ok!(Kitties::buy(Origin::signed(2), 0, 100));
+
+            assert_eq!(Kitties::kitty_owner(0), Some(2));
+            assert_eq!(Kitties::kitty_price(0), None); // 成交后下架
+            assert_eq!(pallet_balances::Module::<Test>::free_balance(1), 10000 - 10 + 100);
+            assert_eq!(pallet_balances::Module::<Test>::free_balance(2), 11000 - 100);
+            assert_eq!(pallet_balances::Module::<Test>::reserved_balance(2), 10); // 押金随kitty转移
+            let sold_event = TestEvent::kitties_event(Event::<Test>::Sold(1, 2, 0, 100));
+            assert_eq!(System::events().last().unwrap().event, sold_event);
+        })
+    }
+
+    /// 未挂单、出价不足、购买自己的kitty
+    #[test]
+    fn buy_kitty_failed() {
+        new_test_ext().execute_with(|| {
+            run_to_block(10);
+            assert_ok!(Kitties::create(Origin::signed(1)));
+            assert_noop!(Kitties::buy(Origin::signed(2), 0, 100), Error::<Test>::NotForSale);
+            assert_noop!(Kitties::set_price(Origin::signed(2), 0, Some(100)), Error::<Test>::NotKittyOwner);
+            assert_ok!(Kitties::set_price(Origin::signed(1), 0, Some(100)));
+            assert_noop!(Kitties::buy(Origin::signed(2), 0, 99), Error::<Test>::PriceTooLow);
+            assert_noop!(Kitties::buy(Origin::signed(1), 0, 100), Error::<Test>::BuyerIsOwner);
+            assert_ok!(Kitties::set_price(Origin::signed(1), 0, None)); // 撤单
+            assert_noop!(Kitties::buy(Origin::signed(2), 0, 100), Error::<Test>::NotForSale);
         })
     }
 
-}
\ No newline at end of file
+    /// transfer后挂单自动清除
+    #[test]
+    fn transfer_clears_listing() {
+        new_test_ext().execute_with(|| {
+            run_to_block(10);
+            assert_ok!(Kitties::create(Origin::signed(1)));
+            assert_ok!(Kitties::set_price(Origin::signed(1), 0, Some(100)));
+            assert_ok!(Kitties::transfer(Origin::signed(1), 2, 0));
+            assert_eq!(Kitties::kitty_price(0), None);
+            assert_noop!(Kitties::buy(Origin::signed(3), 0, 100), Error::<Test>::NotForSale);
+        })
+    }
+
+    /// 拍卖：被超过的出价退还，结束时卖方收款
+    #[test]
+    fn auction_settles_to_best_bidder() {
+        new_test_ext().execute_with(|| {
+            run_to_block(10);
+            assert_ok!(Kitties::create(Origin::signed(1)));
+            assert_ok!(Kitties::create_auction(Origin::signed(1), 0, 100, 12));
+            assert_noop!(Kitties::bid(Origin::signed(2), 0, 99), Error::<Test>::BidTooLow);
+            assert_ok!(Kitties::bid(Origin::signed(2), 0, 100));
+            assert_noop!(Kitties::bid(Origin::signed(3), 0, 100), Error::<Test>::BidTooLow);
+            assert_ok!(Kitties::bid(Origin::signed(3), 0, 150));
+            assert_eq!(pallet_balances::Module::<Test>::reserved_balance(2), 0); // 被超过，退还
+            assert_eq!(pallet_balances::Module::<Test>::reserved_balance(3), 150);
+
+            run_to_block(13);
+            assert_eq!(Kitties::auction_of(0), None);
+            assert_eq!(Kitties::kitty_owner(0), Some(3));
+            assert_eq!(pallet_balances::Module::<Test>::reserved_balance(3), 10); // 出价已支付，仅剩押金
+            assert_eq!(pallet_balances::Module::<Test>::free_balance(1), 10000 - 10 + 150);
+            assert_eq!(pallet_balances::Module::<Test>::free_balance(3), 12000 - 150);
+            let settled_event = TestEvent::kitties_event(Event::<Test>::AuctionSettled(1, 3, 0, 150));
+            assert!(System::events().iter().any(|r| r.event == settled_event));
+        })
+    }
+
+    /// 无人出价流拍，结束后不能再出价
+    #[test]
+    fn auction_without_bids_is_unsold() {
+        new_test_ext().execute_with(|| {
+            run_to_block(10);
+            assert_ok!(Kitties::create(Origin::signed(1)));
+            assert_noop!(Kitties::create_auction(Origin::signed(1), 0, 100, 10), Error::<Test>::InvalidEndBlock);
+            assert_ok!(Kitties::create_auction(Origin::signed(1), 0, 100, 11));
+            assert_noop!(Kitties::bid(Origin::signed(1), 0, 100), Error::<Test>::BuyerIsOwner);
+            run_to_block(12);
+            assert_eq!(Kitties::kitty_owner(0), Some(1));
+            assert_noop!(Kitties::bid(Origin::signed(2), 0, 100), Error::<Test>::AuctionNotFound);
+            let unsold_event = TestEvent::kitties_event(Event::<Test>::AuctionUnsold(1, 0));
+            assert!(System::events().iter().any(|r| r.event == unsold_event));
+        })
+    }
+
+    /// 拍卖中的kitty不能转移、繁育、挂单
+    #[test]
+    fn auctioned_kitty_is_locked() {
+        new_test_ext().execute_with(|| {
+            run_to_block(10);
+            assert_ok!(Kitties::create(Origin::signed(1)));
+            assert_ok!(Kitties::create(Origin::signed(1)));
+            assert_ok!(Kitties::create_auction(Origin::signed(1), 0, 100, 20));
+            assert_noop!(Kitties::transfer(Origin::signed(1), 2, 0), Error::<Test>::KittyLocked);
+            assert_noop!(Kitties::breed(Origin::signed(1), 0, 1), Error::<Test>::KittyLocked);
+            assert_noop!(Kitties::set_price(Origin::signed(1), 0, Some(1)), Error::<Test>::KittyLocked);
+            assert_noop!(Kitties::create_auction(Origin::signed(1), 0, 100, 20), Error::<Test>::KittyLocked);
+        })
+    }
+
+    /// 同一区块结束的拍卖数量和拍卖时长受限
+    #[test]
+    fn auction_limits() {
+        new_test_ext().execute_with(|| {
+            run_to_block(10);
+            for _ in 0..3 {
+                assert_ok!(Kitties::create(Origin::signed(1)));
+            }
+            assert_noop!(Kitties::create_auction(Origin::signed(1), 0, 100, 111), Error::<Test>::AuctionTooLong);
+            assert_ok!(Kitties::create_auction(Origin::signed(1), 0, 100, 110));
+            assert_ok!(Kitties::create_auction(Origin::signed(1), 1, 100, 110));
+            assert_noop!(Kitties::create_auction(Origin::signed(1), 2, 100, 110), Error::<Test>::TooManyAuctions);
+            assert_ok!(Kitties::create_auction(Origin::signed(1), 2, 100, 109));
+        })
+    }
+
+    /// 基因编解码
+    #[test]
+    fn genome_round_trip() {
+        let genome = Genome::from_dna(&[255u8; 16]);
+        assert_eq!(genome.body_colour, 255 % genome::BODY_COLOURS);
+        assert_eq!(genome.eye_shape, EyeShape::Wide);
+        assert_eq!(genome.pattern, Pattern::Solid); // 255 % 5 == 0
+        assert_eq!(genome.generation, u16::max_value());
+        assert_eq!(Genome::from_dna(&genome.to_dna()), genome);
+    }
+
+    /// 每个基因取自父或母
+    #[test]
+    fn genome_breed_picks_parent_genes() {
+        let mut p1 = Genome::founder(&[0u8; 16]);
+        p1.body_colour = 3;
+        p1.pattern = Pattern::Calico;
+        let mut p2 = Genome::founder(&[0u8; 16]);
+        p2.body_colour = 9;
+        p2.pattern = Pattern::Tabby;
+        p2.generation = 4;
+
+        let child = Genome::breed(&p1, &p2, &[1u8; 16]);
+        assert_eq!((child.body_colour, child.pattern), (3, Pattern::Calico));
+        let child = Genome::breed(&p1, &p2, &[0u8; 16]);
+        assert_eq!((child.body_colour, child.pattern), (9, Pattern::Tabby));
+        assert_eq!(child.generation, 5);
+        assert_eq!(child.cooldown_class, 2);
+    }
+
+    /// 多代繁育后基因仍在取值范围内
+    #[test]
+    fn genes_stay_in_range_after_many_generations() {
+        new_test_ext().execute_with(|| {
+            run_to_block(10);
+            assert_ok!(Kitties::create(Origin::signed(1))); // 0
+            let mut latest = 0;
+            for _ in 0..20 {
+                // 每代与新的初代kitty繁育，避免近亲
+                assert_ok!(Kitties::create(Origin::signed(1)));
+                let founder = Kitties::kitties_count() - 1;
+                assert_ok!(Kitties::breed(Origin::signed(1), latest, founder));
+                latest = Kitties::kitties_count() - 1;
+                run_to_block(System::block_number() + 5 * (genome::MAX_COOLDOWN_CLASS as u64 + 1));
+            }
+            for id in 0..Kitties::kitties_count() {
+                let kitty = Kitties::kitties(id).unwrap();
+                let genome = kitty.genome();
+                assert!(genome.body_colour < genome::BODY_COLOURS);
+                assert!(genome.eye_colour < genome::EYE_COLOURS);
+                assert!(genome.cooldown_class <= genome::MAX_COOLDOWN_CLASS);
+                assert_eq!(Genome::from_dna(&kitty.0), genome);
+            }
+            assert_eq!(Kitties::kitties(latest).unwrap().generation(), 20);
+            assert_eq!(Kitties::kitties(latest).unwrap().cooldown_class(), genome::MAX_COOLDOWN_CLASS);
+        })
+    }
+
+    /// 冷却期内不能繁育，冷却结束后可以
+    #[test]
+    fn breeding_on_cooldown() {
+        new_test_ext().execute_with(|| {
+            run_to_block(10);
+            assert_ok!(Kitties::create(Origin::signed(1))); // 0
+            assert_ok!(Kitties::create(Origin::signed(1))); // 1
+            assert_ok!(Kitties::create(Origin::signed(1))); // 2
+            assert_ok!(Kitties::breed(Origin::signed(1), 0, 1)); // 3
+            assert_eq!(Kitties::next_breed_at(0), 15); // 初代冷却 5 * 1
+            assert_noop!(Kitties::breed(Origin::signed(1), 0, 1), Error::<Test>::BreedingOnCooldown);
+            assert_noop!(Kitties::breed(Origin::signed(1), 2, 1), Error::<Test>::BreedingOnCooldown);
+            assert_ok!(Kitties::breed(Origin::signed(1), 2, 3)); // 新生的kitty没有冷却
+            run_to_block(14);
+            assert_noop!(Kitties::breed(Origin::signed(1), 0, 1), Error::<Test>::BreedingOnCooldown);
+            run_to_block(15);
+            assert_ok!(Kitties::breed(Origin::signed(1), 0, 1));
+        })
+    }
+
+    /// 孩子的代数为父母最大代数加一，冷却随代数增长
+    #[test]
+    fn generation_and_cooldown_grow() {
+        new_test_ext().execute_with(|| {
+            run_to_block(10);
+            assert_ok!(Kitties::create(Origin::signed(1))); // 0
+            assert_ok!(Kitties::create(Origin::signed(1))); // 1
+            assert_ok!(Kitties::breed(Origin::signed(1), 0, 1)); // 2, 第1代
+            assert_ok!(Kitties::create(Origin::signed(1))); // 3
+            assert_ok!(Kitties::breed(Origin::signed(1), 2, 3)); // 4, 第2代
+            assert_eq!(Kitties::kitties(2).unwrap().generation(), 1);
+            assert_eq!(Kitties::kitties(4).unwrap().generation(), 2);
+            assert_ok!(Kitties::create(Origin::signed(1))); // 5
+            assert_ok!(Kitties::breed(Origin::signed(1), 4, 5)); // 6
+            assert_eq!(Kitties::kitties(4).unwrap().cooldown_class(), 1);
+            assert_eq!(Kitties::next_breed_at(4), 10 + 5 * 2); // 冷却等级 1
+        })
+    }
+
+    /// 租用他人的kitty配种，孩子归调用者
+    #[test]
+    fn breed_with_sire_works() {
+        new_test_ext().execute_with(|| {
+            run_to_block(10);
+            assert_ok!(Kitties::create(Origin::signed(1))); // 0
+            assert_ok!(Kitties::create(Origin::signed(2))); // 1
+            assert_noop!(Kitties::breed_with_sire(Origin::signed(2), 1, 0, 50), Error::<Test>::SireNotOffered);
+            assert_noop!(Kitties::offer_sire(Origin::signed(2), 0, 50), Error::<Test>::NotKittyOwner);
+            assert_ok!(Kitties::offer_sire(Origin::signed(1), 0, 50));
+            assert_noop!(Kitties::breed_with_sire(Origin::signed(3), 1, 0, 50), Error::<Test>::NotKittyOwner);
+            // 配种费超过 max_fee 时失败
+            assert_noop!(Kitties::breed_with_sire(Origin::signed(2), 1, 0, 49), Error::<Test>::SireFeeTooHigh);
+
+            assert_ok!(Kitties::breed_with_sire(Origin::signed(2), 1, 0, 50)); // 2
+            assert_eq!(Kitties::kitty_owner(2), Some(2));
+            assert_eq!(KittyParents::<Test>::get(2), (1, 0));
+            assert_eq!(pallet_balances::Module::<Test>::free_balance(1), 10000 - 10 + 50);
+            assert_eq!(pallet_balances::Module::<Test>::free_balance(2), 11000 - 10 - 50 - 10);
+            // 冷却中不能再次租用，也不扣费
+            assert_noop!(Kitties::breed_with_sire(Origin::signed(2), 2, 0, 50), Error::<Test>::BreedingOnCooldown);
+        })
+    }
+
+    /// 不能直接用他人的kitty繁育
+    #[test]
+    fn breed_requires_owning_both_parents() {
+        new_test_ext().execute_with(|| {
+            run_to_block(10);
+            assert_ok!(Kitties::create(Origin::signed(1))); // 0
+            assert_ok!(Kitties::create(Origin::signed(2))); // 1
+            assert_noop!(Kitties::breed(Origin::signed(2), 1, 0), Error::<Test>::NotKittyOwner);
+        })
+    }
+
+    /// 取消出租，转移后出租自动清除
+    #[test]
+    fn sire_offer_cleared() {
+        new_test_ext().execute_with(|| {
+            run_to_block(10);
+            assert_ok!(Kitties::create(Origin::signed(1))); // 0
+            assert_ok!(Kitties::create(Origin::signed(2))); // 1
+            assert_ok!(Kitties::offer_sire(Origin::signed(1), 0, 50));
+            assert_ok!(Kitties::cancel_sire_offer(Origin::signed(1), 0));
+            assert_noop!(Kitties::cancel_sire_offer(Origin::signed(1), 0), Error::<Test>::SireNotOffered);
+            assert_ok!(Kitties::offer_sire(Origin::signed(1), 0, 50));
+            assert_ok!(Kitties::transfer(Origin::signed(1), 3, 0));
+            assert_eq!(Kitties::sire_fee(0), None);
+            assert_noop!(Kitties::breed_with_sire(Origin::signed(2), 1, 0, 50), Error::<Test>::SireNotOffered);
+        })
+    }
+
+    /// 释放kitty，血统记录保留，押金扣除 LineageDeposit 后退还
+    #[test]
+    fn release_kitty_works() {
+        new_test_ext().execute_with(|| {
+            run_to_block(10);
+            assert_ok!(Kitties::create(Origin::signed(1))); // 0
+            assert_ok!(Kitties::create(Origin::signed(1))); // 1
+            assert_ok!(Kitties::breed(Origin::signed(1), 0, 1)); // 2
+            assert_eq!(pallet_balances::Module::<Test>::reserved_balance(1), 30);
+            assert_noop!(Kitties::release(Origin::signed(2), 0), Error::<Test>::NotKittyOwner);
+
+            assert_ok!(Kitties::release(Origin::signed(1), 0));
+            assert_eq!(pallet_balances::Module::<Test>::reserved_balance(1), 20);
+            assert_eq!(pallet_balances::Module::<Test>::free_balance(1), 10000 - 30 + 10 - 2);
+            assert!(Kitties::kitties(0).is_none());
+            assert_eq!(Kitties::kitty_owner(0), None);
+            assert!(!AccountKitties::<Test>::contains_key(1, 0));
+            // 血统记录不变
+            assert_eq!(KittyChidren::<Test>::iter_prefix_values(0).count(), 1);
+            assert_eq!(KittyMate::<Test>::get((0, 1)), Some(0));
+            assert_eq!(KittyParents::<Test>::get(2), (0, 1));
+            assert_eq!(KittyChidren::<Test>::iter_prefix_values(1).count(), 1);
+            let released_event = TestEvent::kitties_event(Event::<Test>::Released(1, 0));
+            assert_eq!(System::events().last().unwrap().event, released_event);
+        })
+    }
+
+    /// 没有血统记录的kitty释放时全额退还押金
+    #[test]
+    fn release_founder_refunds_full_deposit() {
+        new_test_ext().execute_with(|| {
+            run_to_block(10);
+            assert_ok!(Kitties::create(Origin::signed(1)));
+            assert_ok!(Kitties::release(Origin::signed(1), 0));
+            assert_eq!(pallet_balances::Module::<Test>::reserved_balance(1), 0);
+            assert_eq!(pallet_balances::Module::<Test>::free_balance(1), 10000);
+        })
+    }
+
+    /// 升级时为仍由创建者持有的旧kitty补记押金，已转移的不补记
+    #[test]
+    fn migrate_to_v2_backfills_deposits() {
+        new_test_ext().execute_with(|| {
+            run_to_block(10);
+            assert_ok!(Kitties::create(Origin::signed(1))); // 0
+            assert_ok!(Kitties::create(Origin::signed(1))); // 1
+            assert_ok!(Kitties::transfer(Origin::signed(1), 3, 1));
+            // 构造旧版本状态：没有押金记录，kitty 1 的押金仍在创建者 1 账号
+            KittyDeposits::<Test>::remove_all();
+            assert_ok!(pallet_balances::Module::<Test>::repatriate_reserved(&3, &1, 10, BalanceStatus::Reserved));
+            StorageVersion::put(Releases::V1);
+
+            Kitties::on_runtime_upgrade();
+            assert_eq!(StorageVersion::get(), Releases::V2);
+            assert_eq!(Kitties::kitty_deposit(0), Some((1, 10)));
+            // 3 没有保留余额，kitty 1 无法认定质押账号
+            assert_eq!(Kitties::kitty_deposit(1), None);
+
+            // 再次执行不重复补记
+            Kitties::on_runtime_upgrade();
+            assert_eq!(KittyDeposits::<Test>::iter().count(), 1);
+
+            assert_ok!(Kitties::release(Origin::signed(1), 0));
+            assert_ok!(Kitties::release(Origin::signed(3), 1));
+            assert_eq!(pallet_balances::Module::<Test>::reserved_balance(1), 10); // 未认定的押金仍锁定
+            assert_eq!(pallet_balances::Module::<Test>::reserved_balance(3), 0);
+        })
+    }
+
+    /// 释放孩子后父母的孩子列表保留
+    #[test]
+    fn release_child_keeps_lineage() {
+        new_test_ext().execute_with(|| {
+            run_to_block(10);
+            assert_ok!(Kitties::create(Origin::signed(1))); // 0
+            assert_ok!(Kitties::create(Origin::signed(1))); // 1
+            assert_ok!(Kitties::breed(Origin::signed(1), 0, 1)); // 2
+            assert_ok!(Kitties::release(Origin::signed(1), 2));
+            assert_eq!(Kitties::descendants(0, 1), vec![2]);
+            assert_eq!(Kitties::descendants(1, 1), vec![2]);
+            assert_eq!(KittyParents::<Test>::get(2), (0, 1));
+            assert_noop!(Kitties::release(Origin::signed(1), 2), Error::<Test>::InvalidKittyId);
+        })
+    }
+
+    /// 拍卖中的kitty不能释放
+    #[test]
+    fn release_locked_kitty_fails() {
+        new_test_ext().execute_with(|| {
+            run_to_block(10);
+            assert_ok!(Kitties::create(Origin::signed(1)));
+            assert_ok!(Kitties::create_auction(Origin::signed(1), 0, 100, 20));
+            assert_noop!(Kitties::release(Origin::signed(1), 0), Error::<Test>::KittyLocked);
+        })
+    }
+
+    /// 所有账号的保留余额之和 == 存活kitty数 * ReserveAmount
+    fn assert_deposit_invariant() {
+        let reserved: u64 = (1..=5).map(|who| pallet_balances::Module::<Test>::reserved_balance(who)).sum();
+        assert_eq!(reserved, crate::Kitties::<Test>::iter().count() as u64 * ReserveAmount::get());
+        for (kitty_id, _) in crate::Kitties::<Test>::iter() {
+            let (holder, amount) = Kitties::kitty_deposit(kitty_id).unwrap();
+            assert_eq!(Some(holder), Kitties::kitty_owner(kitty_id)); // 押金由所有者质押
+            assert_eq!(amount, ReserveAmount::get());
+        }
+    }
+
+    /// 不能转移给不存在的账户，接收方有余额后可以转移
+    #[test]
+    fn transfer_failed_when_recipient_has_no_account() {
+        new_test_ext().execute_with(|| {
+            run_to_block(10);
+            assert_ok!(Kitties::create(Origin::signed(1)));
+            assert_noop!(Kitties::transfer(Origin::signed(1), 6, 0), Error::<Test>::RecipientNotFound);
+            pallet_balances::Module::<Test>::make_free_balance_be(&6, 100);
+            assert_ok!(Kitties::transfer(Origin::signed(1), 6, 0));
+            assert_eq!(Kitties::kitty_owner(0), Some(6));
+            assert_eq!(pallet_balances::Module::<Test>::reserved_balance(6), 10);
+        })
+    }
+
+    /// transfer后押金随所有权转移
+    #[test]
+    fn transfer_moves_reserved_deposit() {
+        new_test_ext().execute_with(|| {
+            run_to_block(10);
+            assert_ok!(Kitties::create(Origin::signed(1)));
+            assert_ok!(Kitties::transfer(Origin::signed(1), 2, 0));
+            assert_eq!(pallet_balances::Module::<Test>::reserved_balance(1), 0);
+            assert_eq!(pallet_balances::Module::<Test>::reserved_balance(2), 10);
+            assert_eq!(pallet_balances::Module::<Test>::free_balance(1), 10000 - 10);
+            assert_eq!(pallet_balances::Module::<Test>::free_balance(2), 11000);
+            assert_eq!(Kitties::kitty_deposit(0), Some((2, 10)));
+            assert_deposit_invariant();
+        })
+    }
+
+    /// 创建、繁育、转移、购买、租用配种、释放后押金总额不变量成立
+    #[test]
+    fn deposit_invariant_holds() {
+        new_test_ext().execute_with(|| {
+            run_to_block(10);
+            assert_ok!(Kitties::create(Origin::signed(1))); // 0
+            assert_ok!(Kitties::create(Origin::signed(1))); // 1
+            assert_ok!(Kitties::breed(Origin::signed(1), 0, 1)); // 2
+            assert_deposit_invariant();
+            assert_ok!(Kitties::transfer(Origin::signed(1), 2, 2));
+            assert_ok!(Kitties::set_price(Origin::signed(1), 1, Some(100)));
+            assert_ok!(Kitties::buy(Origin::signed(3), 1, 100));
+            assert_deposit_invariant();
+            assert_ok!(Kitties::create(Origin::signed(3))); // 3
+            assert_ok!(Kitties::offer_sire(Origin::signed(3), 3, 20));
+            assert_ok!(Kitties::breed_with_sire(Origin::signed(2), 2, 3, 20)); // 4
+            assert_deposit_invariant();
+            assert_ok!(Kitties::release(Origin::signed(1), 0));
+            assert_ok!(Kitties::release(Origin::signed(2), 4));
+            assert_deposit_invariant();
+        })
+    }
+
+    /// 0,1 -> 2,3; 2,4 -> 5
+    fn build_family() {
+        run_to_block(10);
+        assert_ok!(Kitties::create(Origin::signed(1))); // 0
+        assert_ok!(Kitties::create(Origin::signed(1))); // 1
+        assert_ok!(Kitties::breed(Origin::signed(1), 0, 1)); // 2
+        run_to_block(20);
+        assert_ok!(Kitties::breed(Origin::signed(1), 0, 1)); // 3
+        assert_ok!(Kitties::create(Origin::signed(1))); // 4
+        assert_ok!(Kitties::breed(Origin::signed(1), 2, 4)); // 5
+    }
+
+    fn sorted(mut ids: Vec<u32>) -> Vec<u32> {
+        ids.sort();
+        ids
+    }
+
+    /// 祖先与后代
+    #[test]
+    fn ancestors_and_descendants() {
+        new_test_ext().execute_with(|| {
+            build_family();
+            assert_eq!(Kitties::ancestors(5, 1), vec![2, 4]);
+            assert_eq!(Kitties::ancestors(5, 2), vec![2, 4, 0, 1]);
+            assert_eq!(Kitties::ancestors(5, 10), vec![2, 4, 0, 1]);
+            assert_eq!(Kitties::ancestors(0, 10), Vec::<u32>::new());
+            assert_eq!(sorted(Kitties::descendants(0, 1)), vec![2, 3]);
+            assert_eq!(sorted(Kitties::descendants(0, 2)), vec![2, 3, 5]);
+            assert_eq!(Kitties::descendants(5, 2), Vec::<u32>::new());
+        })
+    }
+
+    /// 兄弟姐妹与伴侣
+    #[test]
+    fn siblings_and_mates() {
+        new_test_ext().execute_with(|| {
+            build_family();
+            assert_eq!(Kitties::siblings(2), vec![3]);
+            assert_eq!(Kitties::siblings(5), Vec::<u32>::new());
+            assert_eq!(Kitties::siblings(0), Vec::<u32>::new());
+            assert_eq!(Kitties::mates(0), vec![1]);
+            assert_eq!(Kitties::mates(2), vec![4]);
+            assert_eq!(Kitties::mates(3), Vec::<u32>::new());
+        })
+    }
+
+    /// 父母与孩子、祖孙之间不能繁育
+    #[test]
+    fn breed_with_ancestor_not_allowed() {
+        new_test_ext().execute_with(|| {
+            build_family();
+            run_to_block(100);
+            assert_noop!(Kitties::breed(Origin::signed(1), 0, 2), Error::<Test>::InbreedingNotAllowed);
+            assert_noop!(Kitties::breed(Origin::signed(1), 5, 4), Error::<Test>::InbreedingNotAllowed);
+            assert_noop!(Kitties::breed(Origin::signed(1), 1, 5), Error::<Test>::InbreedingNotAllowed); // 祖孙
+        })
+    }
+
+    /// 释放中间一代后，祖孙之间仍不能繁育
+    #[test]
+    fn breed_with_ancestor_not_allowed_after_release() {
+        new_test_ext().execute_with(|| {
+            build_family();
+            run_to_block(100);
+            assert_ok!(Kitties::release(Origin::signed(1), 2));
+            assert_eq!(Kitties::ancestors(5, 2), vec![2, 4, 0, 1]);
+            assert_eq!(Kitties::mates(4), vec![2]);
+            assert_noop!(Kitties::breed(Origin::signed(1), 5, 0), Error::<Test>::InbreedingNotAllowed);
+            assert_noop!(Kitties::breed(Origin::signed(1), 1, 5), Error::<Test>::InbreedingNotAllowed);
+        })
+    }
+
+    /// 同父同母的兄弟姐妹不能繁育，同父异母可以
+    #[test]
+    fn breed_full_siblings_not_allowed() {
+        new_test_ext().execute_with(|| {
+            build_family();
+            run_to_block(100);
+            assert_noop!(Kitties::breed(Origin::signed(1), 2, 3), Error::<Test>::InbreedingNotAllowed);
+            assert_ok!(Kitties::breed(Origin::signed(1), 3, 5)); // 3 与 5 的父亲 2 是兄弟，非直系
+        })
+    }
+
+    /// 超出检查代数的祖先不再限制
+    #[test]
+    fn inbreeding_depth_is_bounded() {
+        new_test_ext().execute_with(|| {
+            run_to_block(10);
+            assert_ok!(Kitties::create(Origin::signed(1))); // 0
+            let mut latest = 0;
+            for _ in 0..MaxInbreedingDepth::get() {
+                assert_ok!(Kitties::create(Origin::signed(1)));
+                let founder = Kitties::kitties_count() - 1;
+                assert_ok!(Kitties::breed(Origin::signed(1), latest, founder));
+                latest = Kitties::kitties_count() - 1;
+            }
+            run_to_block(100);
+            // 0 是 latest 向上第 MaxInbreedingDepth 代
+            assert_noop!(Kitties::breed(Origin::signed(1), latest, 0), Error::<Test>::InbreedingNotAllowed);
+            assert_ok!(Kitties::create(Origin::signed(1)));
+            let founder = Kitties::kitties_count() - 1;
+            assert_ok!(Kitties::breed(Origin::signed(1), latest, founder));
+            let child = Kitties::kitties_count() - 1;
+            run_to_block(200);
+            assert_ok!(Kitties::breed(Origin::signed(1), child, 0)); // 超出检查代数
+        })
+    }
+
+    /// 表驱动：start_index 取 0、1、7、1000 时，分配从 start_index 开始，[0, start_index) 保留
+    #[test]
+    fn start_index_reserves_range_table() {
+        for &start in [0u32, 1, 7, 1_000].iter() {
+            new_test_ext_with_start(start).execute_with(|| {
+                assert_eq!(Kitties::kitties_count(), start);
+                assert_ok!(Kitties::create(Origin::signed(1)));
+                assert_ok!(Kitties::create(Origin::signed(1)));
+                assert_ok!(Kitties::breed(Origin::signed(1), start, start + 1));
+                assert_eq!(Kitties::kitties_count(), start + 3);
+                assert_eq!(Kitties::kitty_parents(start + 2), (start, start + 1));
+                // [0, start) 不会被分配
+                assert_eq!(crate::Kitties::<Test>::iter().filter(|(id, _)| *id < start).count(), 0);
+            });
+        }
+    }
+
+    /// 表驱动：距 max_value 剩 0 至 3 个 id 时，用完后创建返回 KittiesCountOverFlow，max_value 不分配
+    #[test]
+    fn create_overflows_at_max_value_table() {
+        for remaining in 0u32..4 {
+            new_test_ext_with_start(u32::max_value() - remaining).execute_with(|| {
+                for i in 0..remaining {
+                    assert_ok!(Kitties::create(Origin::signed(1)));
+                    assert!(Kitties::kitties(u32::max_value() - remaining + i).is_some());
+                }
+                assert_eq!(Kitties::kitties_count(), u32::max_value());
+                assert_noop!(Kitties::create(Origin::signed(1)), Error::<Test>::KittiesCountOverFlow);
+                // max_value 本身不会被分配
+                assert!(Kitties::kitties(u32::max_value()).is_none());
+                assert_eq!(AccountKitties::<Test>::iter_prefix_values(1).count(), remaining as usize);
+            });
+        }
+    }
+
+    #[test]
+    fn breed_overflows_at_max_value() {
+        new_test_ext_with_start(u32::max_value() - 2).execute_with(|| {
+            assert_ok!(Kitties::create(Origin::signed(1)));
+            assert_ok!(Kitties::create(Origin::signed(1)));
+            assert_noop!(
+                Kitties::breed(Origin::signed(1), u32::max_value() - 2, u32::max_value() - 1),
+                Error::<Test>::KittiesCountOverFlow
+            );
+            assert_deposit_invariant();
+        });
+    }
+
+    /// id 用尽时租用配种在付费前失败
+    #[test]
+    fn breed_with_sire_overflows_before_paying() {
+        new_test_ext_with_start(u32::max_value() - 2).execute_with(|| {
+            assert_ok!(Kitties::create(Origin::signed(1)));
+            assert_ok!(Kitties::create(Origin::signed(1)));
+            assert_ok!(Kitties::transfer(Origin::signed(1), 2, u32::max_value() - 1));
+            assert_ok!(Kitties::offer_sire(Origin::signed(1), u32::max_value() - 2, 50));
+            assert_noop!(
+                Kitties::breed_with_sire(Origin::signed(2), u32::max_value() - 1, u32::max_value() - 2, 50),
+                Error::<Test>::KittiesCountOverFlow
+            );
+            assert_eq!(pallet_balances::Module::<Test>::free_balance(2), 11000);
+        });
+    }
+}
@@ -0,0 +1,50 @@
+//! 存储迁移
+
+use super::*;
+use frame_support::{StorageValue, StorageMap, IterableStorageMap};
+use sp_runtime::traits::Saturating;
+use sp_std::collections::btree_map::BTreeMap;
+
+/// V1 -> V2：为引入 KittyDeposits 之前创建的kitty补记押金
+///
+/// 旧版本创建、繁育时由调用者质押 ReserveAmount，但没有记录质押账号，转移时押金也留在原账号。
+/// 所有者的保留余额扣除已记录的押金后仍不少于 ReserveAmount 时，认为kitty仍由创建者持有，
+/// 补记 (所有者, ReserveAmount)。同一账号的保留余额不足以覆盖其全部kitty时，只补记覆盖得到的部分。
+///
+/// 其余kitty（已转移给他人，押金仍质押在原创建者账号）无法在链上确定质押账号，不补记：
+/// 释放时不退押金，转移时也不移动押金。原创建者被锁定的保留余额需由 Root 通过
+/// pallet_balances::set_balance 处理。
+pub fn migrate_to_v2<T: Trait>() -> Weight {
+    if StorageVersion::get() != Releases::V1 {
+        return T::DbWeight::get().reads(1);
+    }
+
+    let amount = T::ReserveAmount::get();
+    let mut reads: Weight = 1;
+    let mut writes: Weight = 1;
+    // 各账号尚未对应到押金记录的保留余额
+    let mut unattributed: BTreeMap<T::AccountId, BalanceOf<T>> = BTreeMap::new();
+    for (_, (holder, deposit)) in KittyDeposits::<T>::iter() {
+        reads += 1;
+        let available = unattributed.entry(holder.clone())
+            .or_insert_with(|| { reads += 1; T::Currency::reserved_balance(&holder) });
+        *available = available.saturating_sub(deposit);
+    }
+
+    for (kitty_id, owner) in KittyOwners::<T>::iter() {
+        reads += 2;
+        if KittyDeposits::<T>::contains_key(kitty_id) {
+            continue;
+        }
+        let available = unattributed.entry(owner.clone())
+            .or_insert_with(|| { reads += 1; T::Currency::reserved_balance(&owner) });
+        if *available >= amount {
+            *available -= amount;
+            KittyDeposits::<T>::insert(kitty_id, (owner, amount));
+            writes += 1;
+        }
+    }
+    StorageVersion::put(Releases::V2);
+
+    T::DbWeight::get().reads_writes(reads, writes)
+}
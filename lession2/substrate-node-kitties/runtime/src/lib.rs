@@ -59,6 +59,9 @@ pub type AccountIndex = u32;
 /// Balance of an account.
 pub type Balance = u128;
 
+/// Index of a kitty.
+pub type KittyIndex = u32;
+
 /// Index of a transaction in the chain.
 pub type Index = u32;
 
@@ -268,15 +271,24 @@ impl pallet_template::Trait for Runtime {
 }
 
 parameter_types! {
-	pub const KittyIndexValue: u32 = 1;
 	pub const ReserveAmount: u64 = 10;
+	pub const BreedingCooldown: BlockNumber = 10 * MINUTES;
+	pub const MaxInbreedingDepth: u32 = 8;
+	pub const MaxAuctionsPerBlock: u32 = 10;
+	pub const MaxAuctionDuration: BlockNumber = 7 * DAYS;
+	pub const LineageDeposit: u64 = 2;
 }
 impl pallet_kitties::Trait for Runtime {
 	type Event = Event;
 	type Randomness = RandomnessCollectiveFlip;
-	type KittyIndexValue = KittyIndexValue;
+	type KittyIndex = KittyIndex;
 	type Currency = pallet_balances::Module<Runtime>;
 	type ReserveAmount = ReserveAmount;
+	type BreedingCooldown = BreedingCooldown;
+	type MaxInbreedingDepth = MaxInbreedingDepth;
+	type MaxAuctionsPerBlock = MaxAuctionsPerBlock;
+	type MaxAuctionDuration = MaxAuctionDuration;
+	type LineageDeposit = LineageDeposit;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -296,7 +308,7 @@ construct_runtime!(
 		Sudo: pallet_sudo::{Module, Call, Config<T>, Storage, Event<T>},
 		// Include the custom logic from the template pallet in the runtime.
 		TemplateModule: pallet_template::{Module, Call, Storage, Event<T>},
-		KittiesModule: pallet_kitties::{Module, Call, Storage, Event<T>},
+		KittiesModule: pallet_kitties::{Module, Call, Storage, Config<T>, Event<T>},
 	}
 );
 
@@ -459,6 +471,24 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_kitties_runtime_api::KittiesApi<Block, KittyIndex> for Runtime {
+		fn ancestors(kitty_id: KittyIndex, depth: u32) -> Vec<KittyIndex> {
+			KittiesModule::ancestors(kitty_id, depth)
+		}
+
+		fn descendants(kitty_id: KittyIndex, depth: u32) -> Vec<KittyIndex> {
+			KittiesModule::descendants(kitty_id, depth)
+		}
+
+		fn siblings(kitty_id: KittyIndex) -> Vec<KittyIndex> {
+			KittiesModule::siblings(kitty_id)
+		}
+
+		fn mates(kitty_id: KittyIndex) -> Vec<KittyIndex> {
+			KittiesModule::mates(kitty_id)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn dispatch_benchmark(